@@ -0,0 +1,120 @@
+#ifndef MINESWEEPER_H
+#define MINESWEEPER_H
+
+/* Generated by cbindgen from ffi/src/lib.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define MS_OK 0
+
+#define MS_ERR_NULL -1
+
+#define MS_ERR_OUT_OF_BOUNDS -2
+
+#define MS_ERR_INVALID_ARGUMENT -3
+
+/**
+ * The game is already won or lost.
+ */
+#define MS_ERR_GAME_OVER -4
+
+/**
+ * The mines are already placed, so the seed can no longer change.
+ */
+#define MS_ERR_STARTED -5
+
+#define MS_ERR_PANIC -6
+
+#define MS_STATUS_READY 0
+
+#define MS_STATUS_PLAYING 1
+
+#define MS_STATUS_WON 2
+
+#define MS_STATUS_LOST 3
+
+/**
+ * Tiles read back as 0 for empty, 1 to 8 for numbers, or one of these.
+ */
+#define MS_TILE_HIDDEN 9
+
+#define MS_TILE_FLAGGED 10
+
+#define MS_TILE_MINE 11
+
+/**
+ * An opaque game. Create with `ms_board_new`, release with `ms_board_free`.
+ */
+typedef struct MsBoard MsBoard;
+
+/**
+ * Creates a board and stores it in `*out`. Mines are placed on the first dig,
+ * from `seed`.
+ *
+ * # Safety
+ * `out` must be null or valid for writes.
+ */
+int32_t ms_board_new(uint32_t width,
+                     uint32_t height,
+                     uint32_t mines,
+                     uint64_t seed,
+                     struct MsBoard **out);
+
+/**
+ * # Safety
+ * `board` must be null or come from `ms_board_new`, and not be used again.
+ */
+void ms_board_free(struct MsBoard *board);
+
+/**
+ * Digs a tile. Hitting a mine is not an error; check `ms_board_status`.
+ *
+ * # Safety
+ * `board` must be null or a live board from `ms_board_new`.
+ */
+int32_t ms_board_dig(struct MsBoard *board, uint32_t x, uint32_t y);
+
+/**
+ * Toggles a flag on a hidden tile. Flags placed before the first dig are kept
+ * when the mines are placed.
+ *
+ * # Safety
+ * `board` must be null or a live board from `ms_board_new`.
+ */
+int32_t ms_board_flag(struct MsBoard *board, uint32_t x, uint32_t y);
+
+/**
+ * Writes the tile at `(x, y)` to `*out`: 0-8, or one of the `MS_TILE_*` values.
+ *
+ * # Safety
+ * `board` must be null or a live board, and `out` null or valid for writes.
+ */
+int32_t ms_board_tile(const struct MsBoard *board, uint32_t x, uint32_t y, uint8_t *out);
+
+/**
+ * Writes one of the `MS_STATUS_*` values to `*out`.
+ *
+ * # Safety
+ * `board` must be null or a live board, and `out` null or valid for writes.
+ */
+int32_t ms_board_status(const struct MsBoard *board, int32_t *out);
+
+/**
+ * # Safety
+ * `board` must be null or a live board, and `out` null or valid for writes.
+ */
+int32_t ms_board_seed(const struct MsBoard *board, uint64_t *out);
+
+/**
+ * Changes the seed. Only allowed before the first dig.
+ *
+ * # Safety
+ * `board` must be null or a live board from `ms_board_new`.
+ */
+int32_t ms_board_set_seed(struct MsBoard *board, uint64_t seed);
+
+#endif  /* MINESWEEPER_H */
@@ -0,0 +1,212 @@
+//! A C interface to the engine. See `include/minesweeper.h`.
+//!
+//! Every function returns one of the `MS_*` codes and writes results through
+//! out-pointers. Panics are caught and reported as [`MS_ERR_PANIC`], so none
+//! ever unwind into C.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use minesweeper::{Board, GameStatus, TileState};
+
+pub const MS_OK: i32 = 0;
+pub const MS_ERR_NULL: i32 = -1;
+pub const MS_ERR_OUT_OF_BOUNDS: i32 = -2;
+pub const MS_ERR_INVALID_ARGUMENT: i32 = -3;
+/// The game is already won or lost.
+pub const MS_ERR_GAME_OVER: i32 = -4;
+/// The mines are already placed, so the seed can no longer change.
+pub const MS_ERR_STARTED: i32 = -5;
+pub const MS_ERR_PANIC: i32 = -6;
+
+pub const MS_STATUS_READY: i32 = 0;
+pub const MS_STATUS_PLAYING: i32 = 1;
+pub const MS_STATUS_WON: i32 = 2;
+pub const MS_STATUS_LOST: i32 = 3;
+
+/// Tiles read back as 0 for empty, 1 to 8 for numbers, or one of these.
+pub const MS_TILE_HIDDEN: u8 = 9;
+pub const MS_TILE_FLAGGED: u8 = 10;
+pub const MS_TILE_MINE: u8 = 11;
+
+/// An opaque game. Create with `ms_board_new`, release with `ms_board_free`.
+pub struct MsBoard(Board);
+
+fn guard(f: impl FnOnce() -> i32) -> i32 {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(MS_ERR_PANIC)
+}
+
+fn in_bounds(board: &Board, x: u32, y: u32) -> bool {
+    (x as usize) < board.width && (y as usize) < board.height
+}
+
+fn is_over(board: &Board) -> bool {
+    matches!(board.status(), GameStatus::Won | GameStatus::Lost)
+}
+
+/// Creates a board and stores it in `*out`. Mines are placed on the first dig,
+/// from `seed`.
+///
+/// # Safety
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ms_board_new(
+    width: u32,
+    height: u32,
+    mines: u32,
+    seed: u64,
+    out: *mut *mut MsBoard,
+) -> i32 {
+    guard(|| {
+        if out.is_null() {
+            return MS_ERR_NULL;
+        }
+        let (width, height) = (width as usize, height as usize);
+        if !(1..=Board::MAX_SIZE).contains(&width)
+            || !(1..=Board::MAX_SIZE).contains(&height)
+            || mines > Board::max_mines(width, height)
+        {
+            return MS_ERR_INVALID_ARGUMENT;
+        }
+
+        let board = Box::new(MsBoard(Board::with_seed(width, height, mines, seed)));
+        *out = Box::into_raw(board);
+        MS_OK
+    })
+}
+
+/// # Safety
+/// `board` must be null or come from `ms_board_new`, and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn ms_board_free(board: *mut MsBoard) {
+    if !board.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(board))));
+    }
+}
+
+/// Digs a tile. Hitting a mine is not an error; check `ms_board_status`.
+///
+/// # Safety
+/// `board` must be null or a live board from `ms_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ms_board_dig(board: *mut MsBoard, x: u32, y: u32) -> i32 {
+    guard(|| {
+        let Some(MsBoard(board)) = board.as_mut() else {
+            return MS_ERR_NULL;
+        };
+        if !in_bounds(board, x, y) {
+            return MS_ERR_OUT_OF_BOUNDS;
+        }
+        if is_over(board) {
+            return MS_ERR_GAME_OVER;
+        }
+
+        if board.is_generated() {
+            let _ = board.dig(x as usize, y as usize);
+        } else {
+            let _ = board.first_dig(x as usize, y as usize);
+        }
+        MS_OK
+    })
+}
+
+/// Toggles a flag on a hidden tile. Flags placed before the first dig are kept
+/// when the mines are placed.
+///
+/// # Safety
+/// `board` must be null or a live board from `ms_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ms_board_flag(board: *mut MsBoard, x: u32, y: u32) -> i32 {
+    guard(|| {
+        let Some(MsBoard(board)) = board.as_mut() else {
+            return MS_ERR_NULL;
+        };
+        if !in_bounds(board, x, y) {
+            return MS_ERR_OUT_OF_BOUNDS;
+        }
+        if is_over(board) {
+            return MS_ERR_GAME_OVER;
+        }
+
+        board.flag(x as usize, y as usize);
+        MS_OK
+    })
+}
+
+/// Writes the tile at `(x, y)` to `*out`: 0-8, or one of the `MS_TILE_*` values.
+///
+/// # Safety
+/// `board` must be null or a live board, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ms_board_tile(board: *const MsBoard, x: u32, y: u32, out: *mut u8) -> i32 {
+    guard(|| {
+        let (Some(MsBoard(board)), false) = (board.as_ref(), out.is_null()) else {
+            return MS_ERR_NULL;
+        };
+        if !in_bounds(board, x, y) {
+            return MS_ERR_OUT_OF_BOUNDS;
+        }
+
+        *out = match board.check(x as usize, y as usize) {
+            TileState::Empty => 0,
+            TileState::Count(n) => n,
+            TileState::Hidden => MS_TILE_HIDDEN,
+            TileState::Flagged => MS_TILE_FLAGGED,
+            TileState::Mine => MS_TILE_MINE,
+        };
+        MS_OK
+    })
+}
+
+/// Writes one of the `MS_STATUS_*` values to `*out`.
+///
+/// # Safety
+/// `board` must be null or a live board, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ms_board_status(board: *const MsBoard, out: *mut i32) -> i32 {
+    guard(|| {
+        let (Some(MsBoard(board)), false) = (board.as_ref(), out.is_null()) else {
+            return MS_ERR_NULL;
+        };
+
+        *out = match board.status() {
+            GameStatus::Ready => MS_STATUS_READY,
+            GameStatus::Playing => MS_STATUS_PLAYING,
+            GameStatus::Won => MS_STATUS_WON,
+            GameStatus::Lost => MS_STATUS_LOST,
+        };
+        MS_OK
+    })
+}
+
+/// # Safety
+/// `board` must be null or a live board, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ms_board_seed(board: *const MsBoard, out: *mut u64) -> i32 {
+    guard(|| {
+        let (Some(MsBoard(board)), false) = (board.as_ref(), out.is_null()) else {
+            return MS_ERR_NULL;
+        };
+
+        *out = board.seed;
+        MS_OK
+    })
+}
+
+/// Changes the seed. Only allowed before the first dig.
+///
+/// # Safety
+/// `board` must be null or a live board from `ms_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ms_board_set_seed(board: *mut MsBoard, seed: u64) -> i32 {
+    guard(|| {
+        let Some(MsBoard(board)) = board.as_mut() else {
+            return MS_ERR_NULL;
+        };
+        if board.is_generated() {
+            return MS_ERR_STARTED;
+        }
+
+        board.seed = seed;
+        MS_OK
+    })
+}
@@ -0,0 +1,44 @@
+//! Builds `tests/ffi/test.c` against the generated header and the cdylib, then
+//! runs it.
+
+#![cfg(unix)]
+
+use std::{env, path::PathBuf, process::Command};
+
+#[test]
+fn c_program_uses_the_api() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // The test binary lives in target/<profile>/deps, next to the library.
+    let exe = env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap();
+    let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ffi_test");
+
+    let cc = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let build = Command::new(&cc)
+        .arg(root.join("tests/ffi/test.c"))
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg("-L")
+        .arg(lib_dir)
+        .arg("-lminesweeper_ffi")
+        .arg("-o")
+        .arg(&out)
+        .status()
+        .unwrap_or_else(|e| panic!("could not run `{}`: {}", cc, e));
+    assert!(build.success(), "test.c failed to build");
+
+    let run = Command::new(&out)
+        .env("LD_LIBRARY_PATH", lib_dir)
+        .env("DYLD_LIBRARY_PATH", lib_dir)
+        .output()
+        .unwrap();
+    assert!(
+        run.status.success(),
+        "test.c failed:\n{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "ok\n");
+}
@@ -0,0 +1,87 @@
+/* Exercises the C API. Built and run by tests/ffi.rs. */
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "minesweeper.h"
+
+#define CHECK(cond)                                                     \
+    do {                                                                \
+        if (!(cond)) {                                                  \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,     \
+                    __LINE__, #cond);                                   \
+            return 1;                                                   \
+        }                                                               \
+    } while (0)
+
+int main(void) {
+    MsBoard *board = NULL;
+    int32_t status;
+    uint64_t seed;
+    uint8_t tile;
+
+    CHECK(ms_board_new(0, 9, 10, 1, &board) == MS_ERR_INVALID_ARGUMENT);
+    CHECK(ms_board_new(3, 3, 9, 1, &board) == MS_ERR_INVALID_ARGUMENT);
+    CHECK(ms_board_new(9, 9, 10, 1, NULL) == MS_ERR_NULL);
+    CHECK(ms_board_dig(NULL, 0, 0) == MS_ERR_NULL);
+
+    CHECK(ms_board_new(9, 9, 10, 1, &board) == MS_OK);
+    CHECK(board != NULL);
+
+    CHECK(ms_board_status(board, &status) == MS_OK);
+    CHECK(status == MS_STATUS_READY);
+
+    CHECK(ms_board_set_seed(board, 42) == MS_OK);
+    CHECK(ms_board_seed(board, &seed) == MS_OK);
+    CHECK(seed == 42);
+
+    CHECK(ms_board_dig(board, 9, 0) == MS_ERR_OUT_OF_BOUNDS);
+
+    /* A flag placed before the first dig survives the mines being placed. */
+    CHECK(ms_board_flag(board, 0, 0) == MS_OK);
+    CHECK(ms_board_dig(board, 4, 4) == MS_OK);
+    CHECK(ms_board_tile(board, 0, 0, &tile) == MS_OK);
+    CHECK(tile == MS_TILE_FLAGGED);
+    CHECK(ms_board_flag(board, 0, 0) == MS_OK);
+    CHECK(ms_board_set_seed(board, 7) == MS_ERR_STARTED);
+
+    CHECK(ms_board_status(board, &status) == MS_OK);
+    CHECK(status == MS_STATUS_PLAYING);
+
+    /* The first dig is always clear of mines. */
+    CHECK(ms_board_tile(board, 4, 4, &tile) == MS_OK);
+    CHECK(tile == 0);
+
+    /* Flag a hidden tile and unflag it again. */
+    for (uint32_t y = 0; y < 9; y++) {
+        for (uint32_t x = 0; x < 9; x++) {
+            CHECK(ms_board_tile(board, x, y, &tile) == MS_OK);
+            if (tile == MS_TILE_HIDDEN) {
+                CHECK(ms_board_flag(board, x, y) == MS_OK);
+                CHECK(ms_board_tile(board, x, y, &tile) == MS_OK);
+                CHECK(tile == MS_TILE_FLAGGED);
+                CHECK(ms_board_flag(board, x, y) == MS_OK);
+                CHECK(ms_board_tile(board, x, y, &tile) == MS_OK);
+                CHECK(tile == MS_TILE_HIDDEN);
+                goto flagged;
+            }
+        }
+    }
+flagged:
+
+    /* Dig everything; the game must end one way or the other. */
+    for (uint32_t y = 0; y < 9; y++) {
+        for (uint32_t x = 0; x < 9; x++) {
+            int32_t result = ms_board_dig(board, x, y);
+            CHECK(result == MS_OK || result == MS_ERR_GAME_OVER);
+        }
+    }
+    CHECK(ms_board_status(board, &status) == MS_OK);
+    CHECK(status == MS_STATUS_LOST || status == MS_STATUS_WON);
+    CHECK(ms_board_dig(board, 0, 0) == MS_ERR_GAME_OVER);
+
+    ms_board_free(board);
+    ms_board_free(NULL);
+
+    printf("ok\n");
+    return 0;
+}
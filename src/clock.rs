@@ -0,0 +1,150 @@
+//! Where game time comes from. Timers read a [`Clock`] instead of
+//! `Instant::now`, so tests and replays can drive time by hand.
+
+use core::{cell::Cell, fmt::Debug, time::Duration};
+
+pub trait Clock: Debug {
+    /// Time since some fixed starting point. Only differences between two
+    /// readings mean anything.
+    fn now(&self) -> Duration;
+}
+
+/// The real time, counted from when the clock was made.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to. Starts at zero.
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    now: Cell<Duration>,
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// Counts time only while running, so pauses are left out.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stopwatch {
+    /// Time banked before the last pause.
+    elapsed: Duration,
+    /// When the stopwatch was last started, if it is running.
+    running_since: Option<Duration>,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts or resumes. Does nothing if already running.
+    pub fn start(&mut self, clock: &(impl Clock + ?Sized)) {
+        if self.running_since.is_none() {
+            self.running_since = Some(clock.now());
+        }
+    }
+
+    /// Does nothing if already paused.
+    pub fn pause(&mut self, clock: &(impl Clock + ?Sized)) {
+        if let Some(since) = self.running_since.take() {
+            self.elapsed += clock.now().saturating_sub(since);
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn elapsed(&self, clock: &(impl Clock + ?Sized)) -> Duration {
+        match self.running_since {
+            Some(since) => self.elapsed + clock.now().saturating_sub(since),
+            None => self.elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn pauses_are_left_out() {
+        let clock = FakeClock::new();
+        let mut watch = Stopwatch::new();
+
+        watch.start(&clock);
+        clock.advance(secs(3));
+        watch.pause(&clock);
+        clock.advance(secs(10));
+        assert_eq!(watch.elapsed(&clock), secs(3));
+        assert!(!watch.is_running());
+
+        watch.start(&clock);
+        clock.advance(secs(2));
+        assert_eq!(watch.elapsed(&clock), secs(5));
+        assert!(watch.is_running());
+    }
+
+    #[test]
+    fn starting_or_pausing_twice_does_nothing() {
+        let clock = FakeClock::new();
+        let mut watch = Stopwatch::new();
+
+        watch.start(&clock);
+        clock.advance(secs(4));
+        watch.start(&clock);
+        clock.advance(secs(1));
+        assert_eq!(watch.elapsed(&clock), secs(5));
+
+        watch.pause(&clock);
+        clock.advance(secs(7));
+        watch.pause(&clock);
+        assert_eq!(watch.elapsed(&clock), secs(5));
+    }
+}
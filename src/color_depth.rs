@@ -0,0 +1,205 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum ColorDepth {
+    #[serde(rename = "truecolor", alias = "24bit")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+/// xterm's default values for the 16 named colors.
+const ANSI: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// Checks `COLORTERM`, then the terminfo entry for `TERM`, then the name of
+    /// `TERM` itself. Anything unrecognised is treated as 16 colors.
+    pub fn detect() -> Self {
+        if let Ok(colorterm) = env::var("COLORTERM") {
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                return ColorDepth::TrueColor;
+            }
+        }
+
+        let Ok(term) = env::var("TERM") else {
+            return ColorDepth::Ansi16;
+        };
+
+        if let Some(colors) = terminfo_colors(&term) {
+            return Self::from_count(colors);
+        }
+
+        if term.ends_with("-direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    fn from_count(colors: i32) -> Self {
+        if colors >= 1 << 24 {
+            ColorDepth::TrueColor
+        } else if colors >= 256 {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// The nearest color this terminal can show.
+    pub fn map(&self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, _) => color,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+            (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => nearest_16((r, g, b)),
+            (ColorDepth::Ansi16, Color::Indexed(i)) => nearest_16(indexed_rgb(i)),
+            _ => color,
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..16 => ANSI[i as usize].1,
+        16..232 => {
+            let i = i - 16;
+            (
+                CUBE[(i / 36) as usize],
+                CUBE[(i / 6 % 6) as usize],
+                CUBE[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + (i - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    (16..=255)
+        .min_by_key(|&i| distance(rgb, indexed_rgb(i)))
+        .unwrap()
+}
+
+/// Grays only match grays and hues only match hues, otherwise a muted red
+/// like the default flag lands closer to dark gray than to red.
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    let (r, g, b) = rgb;
+    let chroma = r.max(g).max(b) - r.min(g).min(b);
+    let grays = [0, 7, 8, 15];
+
+    ANSI.iter()
+        .enumerate()
+        .filter(|(i, _)| grays.contains(i) == (chroma < 40))
+        .min_by_key(|(_, (_, ansi))| distance(rgb, *ansi))
+        .map(|(_, (color, _))| *color)
+        .unwrap()
+}
+
+/// Reads the `colors` capability from the compiled terminfo entry for `term`.
+fn terminfo_colors(term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    let sub = [first.to_string(), format!("{:x}", first as u32)];
+
+    terminfo_dirs()
+        .iter()
+        .flat_map(|dir| sub.iter().map(move |s| dir.join(s).join(term)))
+        .find_map(|path| fs::read(path).ok())
+        .and_then(|data| parse_colors(&data))
+}
+
+fn terminfo_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(Path::new(&home).join(".terminfo"));
+    }
+    if let Ok(list) = env::var("TERMINFO_DIRS") {
+        dirs.extend(list.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    }
+    dirs.extend(
+        [
+            "/etc/terminfo",
+            "/lib/terminfo",
+            "/usr/share/terminfo",
+            "/usr/lib/terminfo",
+        ]
+        .iter()
+        .map(PathBuf::from),
+    );
+
+    dirs
+}
+
+/// See term(5): a header of six 16-bit values, then names, booleans and
+/// numbers. `colors` is the 14th number.
+fn parse_colors(data: &[u8]) -> Option<i32> {
+    const COLORS: usize = 13;
+
+    let header = |i: usize| -> Option<usize> {
+        let bytes = data.get(i * 2..i * 2 + 2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]).max(0) as usize)
+    };
+
+    let width = match header(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let (names, bools, nums) = (header(1)?, header(2)?, header(3)?);
+
+    if COLORS >= nums {
+        return None;
+    }
+
+    let mut offset = 12 + names + bools;
+    offset += offset % 2;
+    offset += COLORS * width;
+
+    let bytes = data.get(offset..offset + width)?;
+    let colors = if width == 2 {
+        i16::from_le_bytes([bytes[0], bytes[1]]) as i32
+    } else {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    };
+
+    (colors >= 0).then_some(colors)
+}
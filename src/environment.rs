@@ -0,0 +1,180 @@
+//! A gym-style wrapper around [`Board`] for training agents.
+//!
+//! Observations are one byte per tile, row by row, so every observation of a
+//! given [`GameConfig`] has the same `width * height` shape:
+//!
+//! | class  | tile                    |
+//! |--------|-------------------------|
+//! | 0      | revealed, no neighbours |
+//! | 1 - 8  | revealed number         |
+//! | 9      | hidden                  |
+//! | 10     | flagged                 |
+//! | 11     | revealed mine           |
+
+use alloc::vec::Vec;
+
+use crate::{player::Action, Board, GameConfig, GameStatus, TileState};
+
+pub const HIDDEN: u8 = 9;
+pub const FLAGGED: u8 = 10;
+pub const MINE: u8 = 11;
+/// How many distinct tile classes an observation can contain.
+pub const TILE_CLASSES: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rewards {
+    /// Given for every tile a step reveals.
+    pub reveal: f32,
+    pub win: f32,
+    pub loss: f32,
+    /// Given when a step changes nothing, e.g. digging a revealed tile.
+    pub no_progress: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self {
+            reveal: 0.1,
+            win: 1.0,
+            loss: -1.0,
+            no_progress: -0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub observation: Vec<u8>,
+    pub reward: f32,
+    pub done: bool,
+}
+
+#[derive(Debug)]
+pub struct Environment {
+    config: GameConfig,
+    rewards: Rewards,
+    board: Board,
+}
+
+impl Environment {
+    /// Panics unless `config` [fits](GameConfig::fits), since its boards could
+    /// never be generated.
+    pub fn new(config: GameConfig, rewards: Rewards) -> Self {
+        assert!(
+            config.fits(),
+            "can't deal a {}x{} board with {} mines",
+            config.width,
+            config.height,
+            config.mines
+        );
+        Self {
+            config,
+            rewards,
+            board: config.board(0),
+        }
+    }
+
+    /// Starts a new game. The same seed and actions always replay the same game.
+    pub fn reset(&mut self, seed: u64) -> Vec<u8> {
+        self.board = self.config.board(seed);
+        self.observation()
+    }
+
+    /// Applies `action`. Once a game is over every step is a no-op with zero
+    /// reward until the next [`reset`](Self::reset).
+    pub fn step(&mut self, action: Action) -> Step {
+        if self.done() {
+            return Step {
+                observation: self.observation(),
+                reward: 0.0,
+                done: true,
+            };
+        }
+
+        let (x, y) = action.position();
+        let before = self.observation();
+
+        if x < self.config.width && y < self.config.height {
+            match action {
+                Action::Dig(x, y) if !self.board.is_generated() => {
+                    let _ = self.board.first_dig(x, y);
+                }
+                Action::Dig(x, y) => {
+                    let _ = self.board.dig(x, y);
+                }
+                Action::Flag(x, y) => self.board.flag(x, y),
+                Action::Chord(x, y) => {
+                    let _ = self.board.chord(x, y);
+                }
+            }
+        }
+
+        let observation = self.observation();
+        let revealed = before
+            .iter()
+            .zip(&observation)
+            .filter(|&(&a, &b)| (a == HIDDEN || a == FLAGGED) && b <= 8)
+            .count();
+
+        let reward = match self.board.status() {
+            GameStatus::Won => self.rewards.win + revealed as f32 * self.rewards.reveal,
+            GameStatus::Lost => self.rewards.loss,
+            _ if before == observation => self.rewards.no_progress,
+            _ => revealed as f32 * self.rewards.reveal,
+        };
+
+        Step {
+            observation,
+            reward,
+            done: self.done(),
+        }
+    }
+
+    pub fn done(&self) -> bool {
+        matches!(self.board.status(), GameStatus::Won | GameStatus::Lost)
+    }
+
+    pub fn observation(&self) -> Vec<u8> {
+        (0..self.config.height)
+            .flat_map(|y| (0..self.config.width).map(move |x| (x, y)))
+            .map(|(x, y)| match self.board.check(x, y) {
+                TileState::Empty => 0,
+                TileState::Count(n) => n,
+                TileState::Hidden => HIDDEN,
+                TileState::Flagged => FLAGGED,
+                TileState::Mine => MINE,
+            })
+            .collect()
+    }
+
+    /// `(height, width)`, the shape of every observation.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.config.height, self.config.width)
+    }
+
+    /// The size of the discrete action space used by [`action`](Self::action).
+    pub fn action_count(&self) -> usize {
+        3 * self.config.width * self.config.height
+    }
+
+    /// Maps a discrete action index to an [`Action`]: digs come first, then
+    /// flags, then chords, each in row-major tile order.
+    pub fn action(&self, index: usize) -> Option<Action> {
+        let tiles = self.config.width * self.config.height;
+        let (x, y) = (
+            index % tiles % self.config.width,
+            index % tiles / self.config.width,
+        );
+
+        match index / tiles {
+            0 => Some(Action::Dig(x, y)),
+            1 => Some(Action::Flag(x, y)),
+            2 => Some(Action::Chord(x, y)),
+            _ => None,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+}
@@ -0,0 +1,175 @@
+//! A line-based protocol for driving a [`Board`] without the terminal UI.
+//!
+//! Every command is one line of whitespace-separated words, and every reply is
+//! one line, except `board` which is followed by one line per row:
+//!
+//! ```text
+//! new <width> <height> <mines> [seed]   ok seed <seed>
+//! dig <x> <y>                           ok <status>
+//! flag <x> <y>                          ok <status>
+//! chord <x> <y>                         ok <status>
+//! tile <x> <y>                          tile <c>
+//! state                                 state <status> width <w> height <h> mines <m> left <l> seed <s>
+//! board                                 board <w> <h>, then <h> rows of <w> tiles
+//! quit
+//! ```
+//!
+//! `<status>` is one of `ready`, `playing`, `won` or `lost`. Tiles are `#`
+//! hidden, `F` flagged, `.` empty, `*` mine and `1`-`8` for counts. Anything
+//! that goes wrong is answered with `error <message>`, and the session carries on.
+
+use std::io::{self, BufRead, Write};
+
+use crate::{Board, GameStatus, TileState};
+
+/// Reads commands from `input` until `quit` or end of input.
+pub fn run<R: BufRead, W: Write>(input: R, mut output: W) -> io::Result<()> {
+    let mut board: Option<Board> = None;
+
+    for line in input.lines() {
+        let line = line?;
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        match words.first() {
+            None => continue,
+            Some(&"quit") => break,
+            Some(_) => match command(&mut board, &words) {
+                Ok(reply) => writeln!(output, "{}", reply)?,
+                Err(e) => writeln!(output, "error {}", e)?,
+            },
+        }
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+fn command(board: &mut Option<Board>, words: &[&str]) -> Result<String, String> {
+    let args = &words[1..];
+
+    if words[0] == "new" {
+        return new_game(board, args);
+    }
+
+    let board = board.as_mut().ok_or("no game, send `new` first")?;
+    let over = matches!(board.status(), GameStatus::Won | GameStatus::Lost);
+
+    if over && ["dig", "flag", "chord"].contains(&words[0]) {
+        return Err("the game is over, send `new` to start another".into());
+    }
+
+    match words[0] {
+        "dig" => {
+            let (x, y) = position(board, args)?;
+            if board.is_generated() {
+                let _ = board.dig(x, y);
+            } else {
+                let _ = board.first_dig(x, y);
+            }
+            Ok(format!("ok {}", status(board)))
+        }
+        "flag" => {
+            let (x, y) = position(board, args)?;
+            board.flag(x, y);
+            Ok(format!("ok {}", status(board)))
+        }
+        "chord" => {
+            let (x, y) = position(board, args)?;
+            let _ = board.chord(x, y);
+            Ok(format!("ok {}", status(board)))
+        }
+        "tile" => {
+            let (x, y) = position(board, args)?;
+            Ok(format!("tile {}", tile(board.check(x, y))))
+        }
+        "state" => Ok(format!(
+            "state {} width {} height {} mines {} left {} seed {}",
+            status(board),
+            board.width,
+            board.height,
+            board.mine_count,
+            board.mines_left,
+            board.seed
+        )),
+        "board" => {
+            let mut reply = format!("board {} {}", board.width, board.height);
+            for y in 0..board.height {
+                reply.push('\n');
+                reply.extend((0..board.width).map(|x| tile(board.check(x, y))));
+            }
+            Ok(reply)
+        }
+        other => Err(format!("unknown command `{}`", other)),
+    }
+}
+
+fn new_game(board: &mut Option<Board>, args: &[&str]) -> Result<String, String> {
+    if args.len() < 3 || args.len() > 4 {
+        return Err("usage: new <width> <height> <mines> [seed]".into());
+    }
+
+    let width: usize = number(args[0])?;
+    let height: usize = number(args[1])?;
+    let mines: u32 = number(args[2])?;
+
+    if !(1..=Board::MAX_SIZE).contains(&width) || !(1..=Board::MAX_SIZE).contains(&height) {
+        return Err(format!(
+            "width and height must be between 1 and {}",
+            Board::MAX_SIZE
+        ));
+    }
+    if mines > Board::max_mines(width, height) {
+        return Err(format!(
+            "at most {} mines fit on a {}x{} board",
+            Board::max_mines(width, height),
+            width,
+            height
+        ));
+    }
+
+    let new = match args.get(3) {
+        Some(seed) => Board::with_seed(width, height, mines, number(seed)?),
+        None => Board::new(width, height, mines),
+    };
+    let reply = format!("ok seed {}", new.seed);
+    *board = Some(new);
+
+    Ok(reply)
+}
+
+fn position(board: &Board, args: &[&str]) -> Result<(usize, usize), String> {
+    let [x, y] = args else {
+        return Err("expected <x> <y>".into());
+    };
+    let (x, y): (usize, usize) = (number(x)?, number(y)?);
+
+    if x >= board.width || y >= board.height {
+        return Err(format!("({}, {}) is off the board", x, y));
+    }
+
+    Ok((x, y))
+}
+
+fn number<T: std::str::FromStr>(word: &str) -> Result<T, String> {
+    word.parse()
+        .map_err(|_| format!("`{}` is not a valid number", word))
+}
+
+fn status(board: &Board) -> &'static str {
+    match board.status() {
+        GameStatus::Ready => "ready",
+        GameStatus::Playing => "playing",
+        GameStatus::Won => "won",
+        GameStatus::Lost => "lost",
+    }
+}
+
+fn tile(state: TileState) -> char {
+    match state {
+        TileState::Hidden => '#',
+        TileState::Flagged => 'F',
+        TileState::Empty => '.',
+        TileState::Mine => '*',
+        TileState::Count(n) => (b'0' + n) as char,
+    }
+}
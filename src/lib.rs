@@ -1,17 +1,58 @@
-use std::fmt;
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+pub mod clock;
+pub mod environment;
+pub mod metrics;
+pub mod player;
+pub mod sandbox;
+pub mod solver;
+
+#[cfg(feature = "std")]
+pub mod headless;
+#[cfg(feature = "std")]
+pub mod runner;
+
+#[cfg(feature = "tui")]
+pub mod color_depth;
+#[cfg(feature = "tui")]
+pub mod config;
+#[cfg(feature = "tui")]
 pub mod lose_menu;
+#[cfg(feature = "tui")]
 pub mod option_menu;
+#[cfg(feature = "tui")]
+pub mod theme;
+#[cfg(feature = "tui")]
 pub mod win_menu;
 
 #[derive(Clone, Debug)]
 struct Tile {
     hidden: bool,
-    flag: bool,
+    mark: Mark,
     mine: bool,
     count: u8,
 }
 
+/// What the player has noted on a hidden tile. Only flags count towards
+/// `mines_left` and chording; the others are notes for the player alone.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Mark {
+    #[default]
+    None,
+    Flag,
+    /// Unsure.
+    Question,
+    /// Believed safe.
+    Safe,
+}
+
 pub enum TileState {
     Hidden,
     Flagged,
@@ -20,30 +61,232 @@ pub enum TileState {
     Count(u8),
 }
 
-#[derive(Debug)]
+/// How a revealed number stands against the flags and safe marks around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberStatus {
+    /// More mines could still go around it.
+    Open,
+    /// Flagged exactly as many times as its number.
+    Satisfied,
+    /// More flags than its number.
+    TooManyFlags,
+    /// Too few tiles left unmarked for the mines it still needs.
+    TooFewTiles,
+}
+
+impl NumberStatus {
+    /// The marks around the number can't all be right.
+    pub fn is_contradiction(&self) -> bool {
+        matches!(self, NumberStatus::TooManyFlags | NumberStatus::TooFewTiles)
+    }
+}
+
+/// What is around a tile.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Surroundings {
+    pub flags: u8,
+    /// Hidden tiles without a flag.
+    pub hidden: u8,
+}
+
+/// A tile as shown once the game is lost and every mine is laid open.
+pub enum PostMortemTile {
+    /// The mine that went off.
+    Detonated,
+    /// A mine that went off earlier and cost a life.
+    Exploded,
+    /// A mine that was never found.
+    Mine,
+    /// A flag on a mine.
+    Flagged,
+    /// A flag on a safe tile.
+    WrongFlag,
+    /// Anything else, as the player saw it.
+    Seen(TileState),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameConfig {
+    pub width: usize,
+    pub height: usize,
+    pub mines: u32,
+    pub safety: SafetyPolicy,
+}
+
+impl GameConfig {
+    pub fn board(&self, seed: u64) -> Board {
+        let mut board = Board::with_seed(self.width, self.height, self.mines, seed);
+        board.safety = self.safety;
+        board
+    }
+
+    /// Whether boards can be dealt from this config: both sides between 1 and
+    /// [`Board::MAX_SIZE`], and no more mines than the safety policy leaves
+    /// room for.
+    pub fn fits(&self) -> bool {
+        (1..=Board::MAX_SIZE).contains(&self.width)
+            && (1..=Board::MAX_SIZE).contains(&self.height)
+            && self.mines <= self.safety.max_mines(self.width, self.height)
+    }
+}
+
+/// Which tiles around the first dig are kept free of mines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SafetyPolicy {
+    /// Mines can go anywhere, so the first dig can lose.
+    None,
+    /// Only the dug tile is safe.
+    FirstTile,
+    /// The dug tile and its neighbours are safe, so it opens up.
+    Opening,
+    /// Every tile within this distance of the dug tile is safe.
+    Radius(u8),
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        SafetyPolicy::Radius(2)
+    }
+}
+
+impl SafetyPolicy {
+    /// Whether a tile `(dx, dy)` away from the first dig must be left clear.
+    pub fn protects(&self, dx: i32, dy: i32) -> bool {
+        match *self {
+            SafetyPolicy::None => false,
+            SafetyPolicy::FirstTile => dx == 0 && dy == 0,
+            SafetyPolicy::Opening => dx.abs() <= 1 && dy.abs() <= 1,
+            SafetyPolicy::Radius(r) => dx * dx + dy * dy <= r as i32 * r as i32,
+        }
+    }
+
+    /// The most mines a board of this size can hold while still keeping the
+    /// first dig clear.
+    pub fn max_mines(&self, width: usize, height: usize) -> u32 {
+        let r = match *self {
+            SafetyPolicy::Radius(r) => r as i32,
+            _ => 1,
+        };
+        let safe = (-r..=r)
+            .flat_map(|dx| (-r..=r).map(move |dy| (dx, dy)))
+            .filter(|&(dx, dy)| self.protects(dx, dy))
+            .count();
+        (width * height).saturating_sub(safe) as u32
+    }
+
+    /// The widest [`SafetyPolicy::Radius`] that still leaves room for `mines`
+    /// on a board of this size, or 0 if not even radius 1 does.
+    pub fn widest_radius(width: usize, height: usize, mines: u32) -> u8 {
+        // Past the longer side, a wider radius protects nothing more.
+        let longest = width.max(height).min(u8::MAX as usize) as u8;
+        (1..=longest)
+            .take_while(|&r| SafetyPolicy::Radius(r).max_mines(width, height) >= mines)
+            .last()
+            .unwrap_or(0)
+    }
+}
+
+impl fmt::Display for SafetyPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SafetyPolicy::None => write!(f, "None"),
+            SafetyPolicy::FirstTile => write!(f, "Tile"),
+            SafetyPolicy::Opening => write!(f, "Opening"),
+            SafetyPolicy::Radius(r) => write!(f, "Radius {}", r),
+        }
+    }
+}
+
+/// What it takes to win.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WinRule {
+    /// Every safe tile is revealed. The mines left are flagged on the way out.
+    #[default]
+    Cleared,
+    /// Every safe tile is revealed and every mine flagged.
+    Flagged,
+}
+
+/// What it takes to chord: dig every unflagged neighbour of a number whose
+/// flags are all placed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ChordMode {
+    /// Never chords.
+    Disabled,
+    /// Digging a satisfied number chords, as does [`Board::chord`].
+    #[default]
+    OnDig,
+    /// Only [`Board::chord`] chords. Digging a revealed number does nothing.
+    Key,
+    /// As `OnDig`, and a number whose hidden neighbours must all be mines
+    /// flags them all at once.
+    ChordFlag,
+}
+
+impl ChordMode {
+    /// Whether digging a revealed number chords it.
+    pub fn on_dig(&self) -> bool {
+        matches!(self, ChordMode::OnDig | ChordMode::ChordFlag)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameStatus {
+    Ready,
+    Playing,
+    Won,
+    Lost,
+}
+
+#[derive(Debug, Clone)]
 pub struct Board {
     grid: Vec<Vec<Tile>>,
+    generated: bool,
+    detonated: Option<(usize, usize)>,
+    /// Mines that cost a life, in the order they went off.
+    exploded: Vec<(usize, usize)>,
+    flags_used: bool,
+    /// Ended as lost without a mine going off.
+    forfeited: bool,
+    /// Lives left, counting the one being played. A mine dug on the last one
+    /// ends the game; any other flags itself and takes a life.
+    pub lives: u32,
     pub width: usize,
     pub height: usize,
     pub mine_count: u32,
     pub mines_left: i32,
+    pub seed: u64,
+    pub win_rule: WinRule,
+    pub chord_mode: ChordMode,
+    /// Only has an effect before the first dig.
+    pub safety: SafetyPolicy,
 }
 
 impl Tile {
     fn new(mine: bool) -> Self {
         Self {
             hidden: true,
-            flag: false,
+            mark: Mark::None,
             mine,
             count: 0,
         }
     }
+
+    fn flagged(&self) -> bool {
+        self.mark == Mark::Flag
+    }
 }
 
-impl std::fmt::Display for Tile {
+impl fmt::Display for Tile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.hidden {
-            write!(f, "{}", if self.flag { "◄" } else { "◼" })
+            let glyph = match self.mark {
+                Mark::None => "◼",
+                Mark::Flag => "◄",
+                Mark::Question => "?",
+                Mark::Safe => "○",
+            };
+            write!(f, "{}", glyph)
         } else if self.mine {
             write!(f, "◉")
         } else if self.count == 0 {
@@ -57,61 +300,128 @@ impl std::fmt::Display for Tile {
 impl TileState {
     fn new(tile: &Tile) -> TileState {
         if tile.hidden {
-            if tile.flag {
+            if tile.flagged() {
                 TileState::Flagged
             } else {
                 TileState::Hidden
             }
         } else if tile.mine {
             TileState::Mine
+        } else if tile.count == 0 {
+            TileState::Empty
         } else {
-            if tile.count == 0 {
-                TileState::Empty
-            } else {
-                TileState::Count(tile.count)
-            }
+            TileState::Count(tile.count)
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl Default for Board {
     fn default() -> Self {
         Self::new(20, 20, 50)
     }
 }
 
-const SAFETY_RADIUS: i32 = 2;
-
 impl Board {
+    /// The widest and tallest a board can be.
+    pub const MAX_SIZE: usize = i8::MAX as usize;
+
+    #[cfg(feature = "std")]
     pub fn new(width: usize, height: usize, mine_count: u32) -> Self {
+        Self::with_rng(width, height, mine_count, &mut rand::thread_rng())
+    }
+
+    /// Draws the seed from `rng`, for when there is no `thread_rng` to use.
+    pub fn with_rng<R: RngCore + ?Sized>(
+        width: usize,
+        height: usize,
+        mine_count: u32,
+        rng: &mut R,
+    ) -> Self {
+        Self::with_seed(width, height, mine_count, rng.next_u64())
+    }
+
+    /// The same seed and first dig always produce the same mines.
+    pub fn with_seed(width: usize, height: usize, mine_count: u32, seed: u64) -> Self {
         Self {
             grid: vec![vec![Tile::new(false); width]; height],
+            generated: false,
+            detonated: None,
+            exploded: Vec::new(),
+            flags_used: false,
+            forfeited: false,
+            lives: 1,
             width,
             height,
             mine_count,
             mines_left: mine_count as i32,
+            seed,
+            win_rule: WinRule::default(),
+            chord_mode: ChordMode::default(),
+            safety: SafetyPolicy::default(),
+        }
+    }
+
+    /// The most mines a board of this size can hold under the default
+    /// [`SafetyPolicy`]. See [`SafetyPolicy::max_mines`] for the others.
+    pub fn max_mines(width: usize, height: usize) -> u32 {
+        SafetyPolicy::default().max_mines(width, height)
+    }
+
+    /// Places the mines, then digs. Marks placed beforehand are kept, except
+    /// on the dug tile. Only [`SafetyPolicy::None`] lets this fail.
+    #[allow(clippy::result_unit_err)]
+    pub fn first_dig(&mut self, x: usize, y: usize) -> Result<(), ()> {
+        self.set_mark(x, y, Mark::None);
+        let mut grid = self.generate_grid_safe(self.width, self.height, self.mine_count, x, y);
+        for (new, old) in grid.iter_mut().flatten().zip(self.grid.iter().flatten()) {
+            new.mark = old.mark;
         }
+        self.grid = grid;
+        self.generated = true;
+        self.dig(x, y)
     }
 
-    pub fn first_dig(&mut self, x: usize, y: usize) {
-        self.grid = self.generate_grid_safe(self.width, self.height, self.mine_count, x, y);
-        let _ = self.dig(x, y);
+    pub fn is_generated(&self) -> bool {
+        self.generated
     }
 
+    pub fn status(&self) -> GameStatus {
+        if self.forfeited {
+            GameStatus::Lost
+        } else if !self.generated {
+            GameStatus::Ready
+        } else if self.detonated.is_some() {
+            GameStatus::Lost
+        } else if self.game_won() {
+            GameStatus::Won
+        } else {
+            GameStatus::Playing
+        }
+    }
+
+    #[allow(clippy::result_unit_err)]
     pub fn dig(&mut self, x: usize, y: usize) -> Result<(), ()> {
-        if self.grid[y][x].flag {
+        if self.grid[y][x].flagged() {
             return Ok(());
         }
 
         if !self.grid[y][x].mine && self.grid[y][x].count == 0 {
             self.flood_dig(x, y);
+        } else if self.grid[y][x].mine && self.grid[y][x].hidden && self.lives > 1 {
+            self.explode(x, y);
         } else if self.grid[y][x].hidden {
             self.grid[y][x].hidden = false;
             if self.grid[y][x].mine {
+                self.detonated = Some((x, y));
                 return Err(());
             }
-        } else if self.is_valid_smart_clear(x, y) {
-            self.smart_clear(x, y)?;
+        } else if self.chord_mode.on_dig() {
+            self.chord(x, y)?;
+        }
+
+        if self.win_rule == WinRule::Cleared && self.game_won() {
+            self.flag_remaining();
         }
 
         Ok(())
@@ -122,6 +432,7 @@ impl Board {
     }
 
     pub fn undo(&mut self, x: usize, y: usize) {
+        self.detonated = None;
         let [ix, iy] = [x as i8, y as i8];
         let dirs = [-1, 0, 1];
 
@@ -139,32 +450,233 @@ impl Board {
     }
 
     pub fn game_won(&self) -> bool {
-        if self.mines_left != 0 {
-            return false;
+        let cleared = self.grid.iter().flatten().all(|t| t.mine == t.hidden);
+        match self.win_rule {
+            WinRule::Cleared => cleared,
+            WinRule::Flagged => {
+                cleared
+                    && self.mines_left == 0
+                    && self.grid.iter().flatten().all(|t| !t.mine || t.flagged())
+            }
         }
+    }
 
-        self.grid
-            .iter()
-            .flatten()
-            .all(|t| (t.mine && t.flag) || !t.hidden)
+    /// Flags every mine still unflagged, once the game is won.
+    fn flag_remaining(&mut self) {
+        for tile in self.grid.iter_mut().flatten() {
+            if tile.mine {
+                tile.mark = Mark::Flag;
+            }
+        }
+        self.mines_left = 0;
     }
 
+    /// Toggles a flag, replacing any other mark.
     pub fn flag(&mut self, x: usize, y: usize) {
-        if self.grid[y][x].hidden {
-            if self.grid[y][x].flag {
-                self.grid[y][x].flag = false;
-                self.mines_left += 1;
-            } else {
-                self.grid[y][x].flag = true;
-                self.mines_left -= 1;
-            }
+        let mark = match self.grid[y][x].mark {
+            Mark::Flag => Mark::None,
+            _ => Mark::Flag,
+        };
+        self.set_mark(x, y, mark);
+    }
+
+    /// Steps a hidden tile through no mark, flag, question and safe.
+    pub fn cycle_mark(&mut self, x: usize, y: usize) {
+        let mark = match self.grid[y][x].mark {
+            Mark::None => Mark::Flag,
+            Mark::Flag => Mark::Question,
+            Mark::Question => Mark::Safe,
+            Mark::Safe => Mark::None,
+        };
+        self.set_mark(x, y, mark);
+    }
+
+    /// Does nothing on revealed tiles.
+    pub fn set_mark(&mut self, x: usize, y: usize, mark: Mark) {
+        let old = self.grid[y][x].mark;
+        if !self.grid[y][x].hidden || old == mark || self.is_exploded(x, y) {
+            return;
+        }
+
+        if old == Mark::Flag {
+            self.mines_left += 1;
+            self.flags_used = true;
+        }
+        if mark == Mark::Flag {
+            self.mines_left -= 1;
+            self.flags_used = true;
+        }
+        self.grid[y][x].mark = mark;
+    }
+
+    /// The mark on a hidden tile. Revealed tiles have none.
+    pub fn mark(&self, x: usize, y: usize) -> Mark {
+        let tile = &self.grid[y][x];
+        if tile.hidden {
+            tile.mark
+        } else {
+            Mark::None
         }
     }
 
+    /// Digs every unflagged neighbour of a revealed number, but only when the
+    /// number of flags around it matches. With [`ChordMode::ChordFlag`], a
+    /// number with just enough hidden neighbours flags them instead. Does
+    /// nothing otherwise, or at all with [`ChordMode::Disabled`].
+    #[allow(clippy::result_unit_err)]
+    pub fn chord(&mut self, x: usize, y: usize) -> Result<(), ()> {
+        if self.chord_mode == ChordMode::Disabled {
+            return Ok(());
+        }
+
+        if self.is_valid_smart_clear(x, y) {
+            self.smart_clear(x, y)?;
+        } else if self.chord_mode == ChordMode::ChordFlag {
+            self.flag_around(x, y);
+        }
+        Ok(())
+    }
+
+    pub fn is_revealed(&self, x: usize, y: usize) -> bool {
+        !self.grid[y][x].hidden
+    }
+
     pub fn check(&self, x: usize, y: usize) -> TileState {
         TileState::new(&self.grid[y][x])
     }
 
+    /// Checks a revealed number against the marks around it, taking flags as
+    /// mines and safe marks as safe. `None` unless the tile is a revealed number.
+    pub fn number_status(&self, x: usize, y: usize) -> Option<NumberStatus> {
+        let tile = &self.grid[y][x];
+        if tile.hidden || tile.mine || tile.count == 0 {
+            return None;
+        }
+
+        let (mut flags, mut open) = (0, 0);
+        let [ix, iy] = [x as i8, y as i8];
+        let dirs = [-1, 0, 1];
+
+        for dx in dirs {
+            for dy in dirs {
+                if !(dx == 0 && dy == 0) && self.in_bounds(ix + dx, iy + dy) {
+                    let tile = &self.grid[(iy + dy) as usize][(ix + dx) as usize];
+
+                    if tile.hidden && tile.flagged() {
+                        flags += 1;
+                    } else if tile.hidden && tile.mark != Mark::Safe {
+                        open += 1;
+                    }
+                }
+            }
+        }
+
+        Some(if flags > tile.count {
+            NumberStatus::TooManyFlags
+        } else if flags + open < tile.count {
+            NumberStatus::TooFewTiles
+        } else if flags == tile.count {
+            NumberStatus::Satisfied
+        } else {
+            NumberStatus::Open
+        })
+    }
+
+    pub fn surroundings(&self, x: usize, y: usize) -> Surroundings {
+        let mut surroundings = Surroundings::default();
+        let [ix, iy] = [x as i8, y as i8];
+        let dirs = [-1, 0, 1];
+
+        for dx in dirs {
+            for dy in dirs {
+                if !(dx == 0 && dy == 0) && self.in_bounds(ix + dx, iy + dy) {
+                    let tile = &self.grid[(iy + dy) as usize][(ix + dx) as usize];
+
+                    if tile.hidden && tile.flagged() {
+                        surroundings.flags += 1;
+                    } else if tile.hidden {
+                        surroundings.hidden += 1;
+                    }
+                }
+            }
+        }
+
+        surroundings
+    }
+
+    /// Whether a chord on `(x, y)` would dig anything: its flags match its
+    /// number and some neighbour is still hidden and unflagged.
+    pub fn can_chord(&self, x: usize, y: usize) -> bool {
+        if self.chord_mode == ChordMode::Disabled || !self.is_valid_smart_clear(x, y) {
+            return false;
+        }
+
+        let [ix, iy] = [x as i8, y as i8];
+        let dirs = [-1, 0, 1];
+
+        dirs.iter().any(|dx| {
+            dirs.iter().any(|dy| {
+                self.in_bounds(ix + dx, iy + dy) && {
+                    let tile = &self.grid[(iy + dy) as usize][(ix + dx) as usize];
+                    tile.hidden && !tile.flagged()
+                }
+            })
+        })
+    }
+
+    /// Whether the player has placed or removed a flag this game. Flags placed
+    /// for them on a win don't count, so a game without this is a no-flag (NF)
+    /// game.
+    pub fn flags_used(&self) -> bool {
+        self.flags_used
+    }
+
+    /// The mine that ended the game, if one has gone off.
+    pub fn detonated(&self) -> Option<(usize, usize)> {
+        self.detonated
+    }
+
+    /// Mines that cost a life. They stay hidden under a flag that can't be
+    /// taken off, so they count towards chords like any other.
+    pub fn exploded(&self) -> &[(usize, usize)] {
+        &self.exploded
+    }
+
+    pub fn is_exploded(&self, x: usize, y: usize) -> bool {
+        self.exploded.contains(&(x, y))
+    }
+
+    /// Spends a life on the mine at `(x, y)`.
+    fn explode(&mut self, x: usize, y: usize) {
+        self.lives -= 1;
+        self.exploded.push((x, y));
+        self.grid[y][x].mark = Mark::Flag;
+        self.mines_left -= 1;
+    }
+
+    /// Ends the game as lost, as when the time runs out.
+    pub fn forfeit(&mut self) {
+        self.forfeited = true;
+    }
+
+    /// Shows where every mine was. `None` until the game is lost or won, since
+    /// it gives the whole board away.
+    pub fn post_mortem(&self, x: usize, y: usize) -> Option<PostMortemTile> {
+        if !matches!(self.status(), GameStatus::Lost | GameStatus::Won) {
+            return None;
+        }
+
+        let tile = &self.grid[y][x];
+        Some(match (tile.mine, tile.hidden && tile.flagged()) {
+            _ if self.detonated == Some((x, y)) => PostMortemTile::Detonated,
+            _ if self.is_exploded(x, y) => PostMortemTile::Exploded,
+            (true, true) => PostMortemTile::Flagged,
+            (false, true) => PostMortemTile::WrongFlag,
+            (true, false) => PostMortemTile::Mine,
+            (false, false) => PostMortemTile::Seen(self.check(x, y)),
+        })
+    }
+
     fn is_valid_smart_clear(&self, x: usize, y: usize) -> bool {
         if self.grid[y][x].count == 0 || self.grid[y][x].hidden {
             return false;
@@ -179,7 +691,7 @@ impl Board {
                 if !(dx == 0 && dy == 0) && self.in_bounds(ix + dx, iy + dy) {
                     let tile = &self.grid[(iy + dy) as usize][(ix + dx) as usize];
 
-                    if tile.hidden && tile.flag {
+                    if tile.hidden && tile.flagged() {
                         count += 1;
                     }
                 }
@@ -198,7 +710,7 @@ impl Board {
                 if !(dx == 0 && dy == 0) && self.in_bounds(ix + dx, iy + dy) {
                     let tile = &self.grid[(iy + dy) as usize][(ix + dx) as usize];
 
-                    if tile.hidden && !tile.flag {
+                    if tile.hidden && !tile.flagged() {
                         self.dig((ix + dx) as usize, (iy + dy) as usize)?;
                     }
                 }
@@ -208,8 +720,40 @@ impl Board {
         Ok(())
     }
 
+    /// Flags every hidden neighbour of a revealed number that has only as many
+    /// hidden neighbours as it has mines.
+    fn flag_around(&mut self, x: usize, y: usize) {
+        let tile = &self.grid[y][x];
+        if tile.hidden || tile.mine || tile.count == 0 {
+            return;
+        }
+
+        let around = self.surroundings(x, y);
+        if around.hidden == 0 || around.flags + around.hidden != tile.count {
+            return;
+        }
+
+        let [ix, iy] = [x as i8, y as i8];
+        let dirs = [-1, 0, 1];
+
+        for dx in dirs {
+            for dy in dirs {
+                if !(dx == 0 && dy == 0) && self.in_bounds(ix + dx, iy + dy) {
+                    let (nx, ny) = ((ix + dx) as usize, (iy + dy) as usize);
+                    if self.grid[ny][nx].hidden {
+                        self.set_mark(nx, ny, Mark::Flag);
+                    }
+                }
+            }
+        }
+    }
+
     fn flood_dig(&mut self, x: usize, y: usize) {
         let tile = &mut self.grid[y][x];
+        // Flags hold back an opening, as they do a dig.
+        if tile.hidden && tile.flagged() {
+            return;
+        }
 
         if tile.count != 0 || !tile.hidden && !tile.mine {
             tile.hidden = false;
@@ -238,30 +782,30 @@ impl Board {
         dig_y: usize,
     ) -> Vec<Vec<Tile>> {
         let mut grid = vec![vec![Tile::new(false); width]; height];
+        let mut rng = StdRng::seed_from_u64(self.seed);
 
         for _ in 0..mine_count {
-            let mut x = ((rand::random::<f32>() * width as f32) as usize).clamp(0, width - 1);
-            let mut y = ((rand::random::<f32>() * height as f32) as usize).clamp(0, height - 1);
+            let mut x = rng.gen_range(0..width);
+            let mut y = rng.gen_range(0..height);
 
-            while grid[y][x].mine || Self::mine_too_close(x, y, dig_x, dig_y) {
-                x = (rand::random::<f32>() * width as f32) as usize;
-                y = (rand::random::<f32>() * height as f32) as usize;
+            while grid[y][x].mine || self.mine_too_close(x, y, dig_x, dig_y) {
+                x = rng.gen_range(0..width);
+                y = rng.gen_range(0..height);
             }
 
             grid[y][x] = Tile::new(true);
         }
 
         self.count_mines(&mut grid, width, height);
-        return grid;
+        grid
     }
 
-    fn mine_too_close(x: usize, y: usize, dig_x: usize, dig_y: usize) -> bool {
-        let dx = x as i32 - dig_x as i32;
-        let dy = y as i32 - dig_y as i32;
-        dx * dx + dy * dy <= SAFETY_RADIUS * SAFETY_RADIUS
+    fn mine_too_close(&self, x: usize, y: usize, dig_x: usize, dig_y: usize) -> bool {
+        self.safety
+            .protects(x as i32 - dig_x as i32, y as i32 - dig_y as i32)
     }
 
-    fn count_mines(&self, grid: &mut Vec<Vec<Tile>>, width: usize, height: usize) {
+    fn count_mines(&self, grid: &mut [Vec<Tile>], width: usize, height: usize) {
         for y in 0..height {
             for x in 0..width {
                 if grid[y][x].mine {
@@ -275,10 +819,11 @@ impl Board {
 
                 for dx in dirs {
                     for dy in dirs {
-                        if !(dx == 0 && dy == 0) && self.in_bounds(ix + dx, iy + dy) {
-                            if grid[(iy + dy) as usize][(ix + dx) as usize].mine {
-                                mine_count += 1;
-                            }
+                        if !(dx == 0 && dy == 0)
+                            && self.in_bounds(ix + dx, iy + dy)
+                            && grid[(iy + dy) as usize][(ix + dx) as usize].mine
+                        {
+                            mine_count += 1;
                         }
                     }
                 }
@@ -289,7 +834,34 @@ impl Board {
     }
 }
 
-impl std::fmt::Display for Board {
+#[cfg(test)]
+impl Board {
+    /// A dealt board drawn one row per string: `*` is a mine, `#` a hidden
+    /// safe tile and `.` a revealed one.
+    pub(crate) fn from_rows(rows: &[&str]) -> Self {
+        let (width, height) = (rows[0].len(), rows.len());
+        let mut grid: Vec<Vec<Tile>> = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .map(|c| Tile {
+                        hidden: c != '.',
+                        ..Tile::new(c == '*')
+                    })
+                    .collect()
+            })
+            .collect();
+        let mines = grid.iter().flatten().filter(|t| t.mine).count() as u32;
+
+        let mut board = Board::with_seed(width, height, mines, 0);
+        board.count_mines(&mut grid, width, height);
+        board.grid = grid;
+        board.generated = true;
+        board
+    }
+}
+
+impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for y in 0..self.height {
             for x in 0..self.width {
@@ -300,7 +872,7 @@ impl std::fmt::Display for Board {
                     if x < self.width - 1 { " " } else { "" }
                 );
             }
-            let _ = write!(f, "\n");
+            let _ = writeln!(f);
         }
 
         Ok(())
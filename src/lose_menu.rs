@@ -4,9 +4,23 @@ use ratatui::{
     prelude::{self, Rect},
     style::{Color, Style, Styled, Stylize},
     text::{Line, Span},
-    widgets::{Block, BorderType, Padding, Paragraph, Widget},
+    widgets::{Block, Padding, Paragraph, Widget},
 };
 
+use crate::{
+    solver::{Review, Verdict},
+    theme::ThemeData,
+};
+
+/// What ended the game.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LoseReason {
+    #[default]
+    Detonated,
+    /// A countdown or time attack ran out.
+    OutOfTime,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct LoseMenu {
     pub continue_select: bool,
@@ -14,13 +28,24 @@ pub struct LoseMenu {
     pub coward: bool,
     pub options: bool,
     pub quit: bool,
+    /// Hide the menu to study the laid-open board.
+    pub post_mortem: bool,
+    /// What the board said about the tile that went off.
+    pub review: Option<Review>,
+    /// Lives the game started with.
+    pub lives: u32,
+    pub reason: LoseReason,
+    /// Boards cleared and their total 3BV, in a time attack.
+    pub score: Option<(u32, u32)>,
+    pub theme: ThemeData,
 }
 
 //TODO fix wronog colour for new game
 impl Widget for &LoseMenu {
     fn render(self, area: Rect, buf: &mut prelude::Buffer) {
+        let theme = &self.theme;
         let layout = Layout::vertical([
-            Constraint::Length(3),
+            Constraint::Length(4),
             Constraint::Length(1),
             Constraint::Length(2),
         ])
@@ -28,42 +53,56 @@ impl Widget for &LoseMenu {
         // Clear.render(area, buf);
 
         let lose_block = Block::bordered()
-            .set_style(
-                Style::new()
-                    .fg(Color::from_u32(0x00303030))
-                    .bg(Color::Reset),
-            )
-            .border_style(Style::new().fg(Color::White).bg(Color::Reset))
+            .set_style(Style::new().fg(theme.menu_dim).bg(theme.menu_bg))
+            .border_style(Style::new().fg(theme.border).bg(theme.menu_bg))
             // .border_type(BorderType::QuadrantInside)
             .title(" Oops! ")
             .title_alignment(Alignment::Center)
+            .title_bottom(" Board [v] ")
             .padding(Padding::proportional(1));
         lose_block.clone().render(area, buf);
 
         let [msg_area, restart_area, continue_area] = layout.areas(lose_block.inner(area));
 
-        Paragraph::new(vec![
-            Line::raw(""),
-            Line::from("You exploded").fg(Color::White),
-            Line::raw(""),
-        ])
-        .centered()
-        .render(msg_area, buf);
+        let mut lines = vec![Line::from(match self.reason {
+            LoseReason::OutOfTime => String::from("Out of time"),
+            LoseReason::Detonated if self.lives > 1 => {
+                format!("Out of all {} lives", self.lives)
+            }
+            LoseReason::Detonated => String::from("You exploded"),
+        })
+        .fg(theme.menu_fg)];
+        if let Some(review) = self.review {
+            lines.push(Line::from(self.verdict()).fg(theme.menu_fg));
+            if review.safe_tile.is_some() {
+                lines.push(Line::from("A safe tile is marked [v]").fg(theme.menu_fg));
+            }
+        }
+        if let Some((boards, bbbv)) = self.score {
+            lines.push(
+                Line::from(format!("Score: {} 3BV, {} boards", bbbv, boards)).fg(theme.menu_fg),
+            );
+        }
+        Paragraph::new(lines).centered().render(msg_area, buf);
 
         Line::from(Span::from("New Game").style(if !self.continue_select {
-            Style::new().bg(Color::DarkGray).fg(Color::White)
+            Style::new().bg(theme.menu_highlight).fg(theme.menu_fg)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme.menu_fg)
         }))
         .centered()
         .render(restart_area, buf);
 
+        if self.reason == LoseReason::OutOfTime {
+            return;
+        }
+
         Paragraph::new(vec![
             Span::from("Continue")
                 .style(if self.continue_select {
-                    Style::new().bg(Color::DarkGray).fg(Color::White)
+                    Style::new().bg(theme.menu_highlight).fg(theme.menu_fg)
                 } else {
-                    Style::default().fg(Color::White)
+                    Style::default().fg(theme.menu_fg)
                 })
                 .into(),
             Span::from("[ dishonourable ]")
@@ -76,12 +115,29 @@ impl Widget for &LoseMenu {
 }
 
 impl LoseMenu {
+    fn verdict(&self) -> String {
+        let Some(review) = self.review else {
+            return String::new();
+        };
+        let percent = (review.probability * 100.0).round();
+        match review.verdict {
+            Verdict::Safe => String::from("Unlucky: it looked safe"),
+            Verdict::Mine => String::from("Mistake: a sure mine"),
+            _ if review.forced() => format!("Forced guess: {}% mine", percent),
+            Verdict::Guess => format!("Needless guess: {}%", percent),
+        }
+    }
+
     pub fn handle_key_event(&mut self, key_event: KeyEvent) {
         match key_event.code {
-            KeyCode::Up | KeyCode::Down => self.continue_select = !self.continue_select,
+            // There is no carrying on once the time is up.
+            KeyCode::Up | KeyCode::Down if self.reason == LoseReason::Detonated => {
+                self.continue_select = !self.continue_select
+            }
             KeyCode::Char('q') | KeyCode::Char('Q') => self.quit = true,
             KeyCode::Char('o') | KeyCode::Char('O') => self.options = true,
             KeyCode::Char('r') | KeyCode::Char('R') => self.restart = true,
+            KeyCode::Char('v') | KeyCode::Char('V') => self.post_mortem = !self.post_mortem,
             KeyCode::Char(' ') | KeyCode::Char('x') | KeyCode::Enter => {
                 if self.continue_select {
                     self.coward = true
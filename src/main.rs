@@ -1,19 +1,29 @@
-use std::{
-    io,
-    time::{Duration, Instant},
-};
+use std::{env, io, rc::Rc, time::Duration};
 
 use color_eyre::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{
+    event::{
+        self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers,
+    },
+    execute,
+};
 use minesweeper::{
+    clock::{Clock, Stopwatch, SystemClock},
+    config::Config,
+    headless,
     lose_menu::*,
+    metrics::Metrics,
     option_menu::{
-        DifficultyOption::{self},
-        OptionMenu, OptionState,
-        SizeOption::{self, Medium, Small},
+        DifficultyOption, FlagKeyOption, ModeOption, OptionMenu, OptionState, SizeOption,
+        MENU_HEIGHT,
     },
+    player::{Player, SolverPlayer},
+    runner,
+    sandbox::Sandbox,
+    solver, theme,
     win_menu::WinMenu,
-    Board, TileState,
+    Board, ChordMode, GameStatus, Mark, NumberStatus, PostMortemTile, SafetyPolicy, TileState,
 };
 use ratatui::{
     buffer::Buffer,
@@ -24,20 +34,93 @@ use ratatui::{
     widgets::{Block, Padding, Paragraph, Widget},
     DefaultTerminal, Frame,
 };
+use strum::IntoEnumIterator;
 
 fn main() -> Result<()> {
     color_eyre::install()?;
 
+    let args = env::args().collect::<Vec<_>>();
+
+    if args.iter().any(|arg| arg == "--headless") {
+        return Ok(headless::run(io::stdin().lock(), io::stdout().lock())?);
+    }
+
+    if let Some(i) = args.iter().position(|arg| arg == "--bench") {
+        let games = args.get(i + 1).and_then(|n| n.parse().ok()).unwrap_or(100);
+        bench(&mut SolverPlayer::new(), games);
+        return Ok(());
+    }
+
+    let config = Config::load()?;
+    let (themes, errors) = theme::load_themes();
+    for error in errors {
+        eprintln!("skipping {}", error);
+    }
+    let options = OptionState::with_themes(themes, config.color_depth());
+
     let mut terminal = ratatui::init();
-    let app_result = App::new(OptionState::default(), &terminal).run(&mut terminal);
+    execute!(io::stdout(), EnableFocusChange)?;
+    let size = terminal.size()?;
+    let app_result = App::new(
+        options,
+        Rc::new(SystemClock::new()),
+        (size.width, size.height),
+    )
+    .run(&mut terminal);
+    execute!(io::stdout(), DisableFocusChange)?;
     ratatui::restore();
     app_result
 }
 
+/// Plays `games` seeded games of every fixed board size and difficulty.
+fn bench<P: Player>(player: &mut P, games: u32) {
+    println!("{} over {} games each", player.name(), games);
+    println!(
+        "{:<8} {:<8} {:>6} {:>9} {:>10} {:>9}",
+        "size", "level", "mines", "win rate", "avg time", "guesses"
+    );
+
+    for size in SizeOption::iter() {
+        let Some((width, height)) = size.dimensions() else {
+            continue;
+        };
+        for difficulty in DifficultyOption::iter() {
+            let config = difficulty.config(width, height, SafetyPolicy::default());
+            let report = runner::run(player, config, games, 0);
+            println!(
+                "{:<8} {:<8} {:>6} {:>8.1}% {:>8.1}ms {:>9.2}",
+                size.to_string(),
+                difficulty.to_string(),
+                config.mines,
+                report.win_rate() * 100.0,
+                report.average_time().as_secs_f64() * 1000.0,
+                report.average_guesses()
+            );
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct App {
     board: minesweeper::Board,
-    start_time: Instant,
+    clock: Rc<dyn Clock>,
+    /// Play time, paused while a menu is open or the terminal is unfocused.
+    timer: Stopwatch,
+    /// Known once the first dig places the mines.
+    metrics: Option<Metrics>,
+    /// Digs and flags so far, for efficiency on the win screen.
+    clicks: u32,
+    /// Chords so far, counted apart from other clicks.
+    chords: u32,
+    /// Fixed for the whole game, whatever the options say meanwhile.
+    mode: ModeOption,
+    time_limit: Duration,
+    /// Lives the game started with.
+    lives: u32,
+    /// Boards cleared and their total 3BV, in a time attack.
+    score: (u32, u32),
+    /// A hypothesis being tried out, if any.
+    sandbox: Option<Sandbox>,
     cursor: (usize, usize),
     state: GameState,
     prev_state: Option<GameState>,
@@ -60,46 +143,69 @@ enum GameState {
 // TODO refactor all style using Stylize
 
 impl App {
-    pub fn new(options: OptionState, terminal: &DefaultTerminal) -> Self {
-        let size = match options.board_size {
-            Small => (15, 10),
-            Medium => (24, 16),
-            SizeOption::Large => (48, 32),
-            SizeOption::Max => {
-                let ta = terminal.size().unwrap();
-                ((ta.width as usize - 6 - 2 + 1) / 2, ta.height as usize - 4)
-            }
-        };
+    /// `terminal` is the width and height of the terminal, which the `Max`
+    /// board size fills.
+    pub fn new(mut options: OptionState, clock: Rc<dyn Clock>, terminal: (u16, u16)) -> Self {
+        options.max_size = (
+            (terminal.0 as usize)
+                .saturating_sub(6 + 2)
+                .div_ceil(2)
+                .clamp(1, Board::MAX_SIZE),
+            (terminal.1 as usize)
+                .saturating_sub(4)
+                .clamp(1, Board::MAX_SIZE),
+        );
+        let size = options.dimensions();
+        let config = options
+            .difficulty
+            .config(size.0, size.1, options.safety_policy());
+        debug_assert!(config.fits());
+        let mut board = config.board(rand::random());
+        board.win_rule = options.win_rule.rule();
+        board.chord_mode = options.chord_mode();
+        board.lives = options.lives.count();
         Self {
-            board: Board::new(
-                size.0,
-                size.1,
-                (match options.difficulty {
-                    DifficultyOption::Easy => 0.12,
-                    DifficultyOption::Medium => 0.15,
-                    DifficultyOption::Hard => 0.17,
-                    DifficultyOption::Expert => 0.20,
-                } * (size.0 * size.1) as f32) as u32,
-            ),
-            start_time: Instant::now(),
+            board,
+            timer: Stopwatch::new(),
+            metrics: None,
+            clicks: 0,
+            chords: 0,
+            mode: options.mode,
+            time_limit: options.time_limit.duration(),
+            lives: options.lives.count(),
+            score: (0, 0),
+            sandbox: None,
+            clock,
             cursor: (size.0 / 2, size.1 / 2),
             state: GameState::Start,
             prev_state: None,
             restart: false,
             quit: false,
+            lose_menu: LoseMenu {
+                theme: options.theme_data.clone(),
+                ..Default::default()
+            },
+            win_menu: WinMenu {
+                theme: options.theme_data.clone(),
+                ..Default::default()
+            },
             option_menu: OptionMenu::new(options),
-            lose_menu: Default::default(),
-            win_menu: Default::default(),
         }
     }
 
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
         while !self.quit {
             if self.restart {
-                *self = Self::new(self.option_menu.state.clone(), terminal);
+                let size = terminal.size()?;
+                *self = Self::new(
+                    self.option_menu.state.clone(),
+                    self.clock.clone(),
+                    (size.width, size.height),
+                );
             }
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_events()?;
+            self.check_time();
         }
         Ok(())
     }
@@ -124,63 +230,89 @@ impl App {
         if self.state == GameState::Options {
             frame.render_widget(
                 &self.option_menu,
-                center(frame.area(), Constraint::Length(59), Constraint::Length(15)),
+                center(
+                    frame.area(),
+                    Constraint::Length(59),
+                    Constraint::Length(MENU_HEIGHT),
+                ),
             );
-        } else if self.state == GameState::Lose {
+        } else if self.state == GameState::Lose && !self.lose_menu.post_mortem {
             frame.render_widget(
                 &self.lose_menu,
-                center(frame.area(), Constraint::Length(31), Constraint::Length(12)),
+                center(frame.area(), Constraint::Length(31), Constraint::Length(13)),
             );
         } else if self.state == GameState::Win {
             frame.render_widget(
                 &self.win_menu,
-                center(frame.area(), Constraint::Length(25), Constraint::Length(7)),
+                center(
+                    frame.area(),
+                    Constraint::Length(25),
+                    Constraint::Length(12 + self.win_menu.lives.is_some() as u16),
+                ),
             );
         }
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
         while event::poll(Duration::from_millis(1))? {
-            if let Event::Key(key_event) = event::read()? {
-                if key_event.kind == KeyEventKind::Press {
-                    match self.state {
-                        GameState::Play | GameState::Start => self.handle_key_event(key_event),
-                        GameState::Options => self.option_menu.handle_key_event(key_event),
-                        GameState::Lose => self.lose_menu.handle_key_event(key_event),
-                        GameState::Win => self.win_menu.handle_key_event(key_event),
+            self.handle_event(event::read()?);
+        }
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::FocusLost => self.timer.pause(&*self.clock),
+            Event::FocusGained if self.clock_running_state() => self.timer.start(&*self.clock),
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                match self.state {
+                    GameState::Play | GameState::Start => self.handle_key_event(key_event),
+                    GameState::Options => {
+                        self.option_menu.handle_key_event(key_event);
+                        self.lose_menu.theme = self.option_menu.state.theme_data.clone();
+                        self.win_menu.theme = self.option_menu.state.theme_data.clone();
+                        self.board.chord_mode = self.option_menu.state.chord_mode();
                     }
+                    GameState::Lose => self.lose_menu.handle_key_event(key_event),
+                    GameState::Win => self.win_menu.handle_key_event(key_event),
+                }
 
-                    if self.option_menu.state.quit || self.lose_menu.quit || self.win_menu.quit {
-                        self.quit();
-                    } else if self.option_menu.state.restart
-                        || self.lose_menu.restart
-                        || self.win_menu.restart
-                    {
-                        self.option_menu.state.restart = false;
-                        self.restart = true;
-                    } else if self.option_menu.state.resume {
-                        self.state = self.prev_state.clone().unwrap_or(GameState::Play);
-                        self.option_menu.state.resume = false;
-                    } else if self.lose_menu.options || self.win_menu.options {
-                        self.lose_menu.options = false;
-                        self.win_menu.options = false;
-                        // TODO maybe change this
-                        self.handle_key_event(KeyEvent::new(
-                            KeyCode::Char('o'),
-                            KeyModifiers::empty(),
-                        ));
-                    } else if self.lose_menu.coward {
-                        self.lose_menu.coward = false;
-                        self.board.undo(self.cursor.0, self.cursor.1);
-                        self.state = GameState::Play;
+                if self.option_menu.state.quit || self.lose_menu.quit || self.win_menu.quit {
+                    self.quit();
+                } else if self.option_menu.state.restart
+                    || self.lose_menu.restart
+                    || self.win_menu.restart
+                {
+                    self.option_menu.state.restart = false;
+                    self.restart = true;
+                } else if self.option_menu.state.resume {
+                    self.state = self.prev_state.clone().unwrap_or(GameState::Play);
+                    self.option_menu.state.resume = false;
+                    if self.clock_running_state() {
+                        self.timer.start(&*self.clock);
                     }
+                } else if self.lose_menu.options || self.win_menu.options {
+                    self.lose_menu.options = false;
+                    self.win_menu.options = false;
+                    // TODO maybe change this
+                    self.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::empty()));
+                } else if self.lose_menu.coward {
+                    self.lose_menu.coward = false;
+                    self.lose_menu.post_mortem = false;
+                    self.board.undo(self.cursor.0, self.cursor.1);
+                    self.state = GameState::Play;
+                    self.timer.start(&*self.clock);
                 }
             }
+            _ => {}
         }
-        Ok(())
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if self.sandbox.is_some() && self.handle_sandbox_key(key_event) {
+            return;
+        }
+
         match key_event.code {
             KeyCode::Left => self.move_cursor(-1, 0),
             KeyCode::Right => self.move_cursor(1, 0),
@@ -190,34 +322,76 @@ impl App {
             KeyCode::Char('x') | KeyCode::Char('X') => {
                 if self.state == GameState::Start {
                     self.state = GameState::Play;
-                    self.start_time = Instant::now();
-                    self.board.first_dig(self.cursor.0, self.cursor.1);
+                    self.timer.start(&*self.clock);
+                    self.clicks += 1;
+                    self.dig(|board, (x, y)| board.first_dig(x, y));
+                } else if self.board.is_revealed(self.cursor.0, self.cursor.1)
+                    && self.board.chord_mode.on_dig()
+                {
+                    self.chord();
                 } else {
-                    if let Err(()) = self.board.dig(self.cursor.0, self.cursor.1) {
-                        self.state = GameState::Lose;
-                    } else if self.board.game_won() {
-                        self.state = GameState::Win;
-                        self.win_menu.time = self.start_time.elapsed().as_secs();
-                    }
+                    self.clicks += 1;
+                    self.dig(|board, (x, y)| board.dig(x, y));
                 }
             }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if self.state == GameState::Play
+                    && self.board.chord_mode != ChordMode::Disabled =>
+            {
+                self.chord();
+            }
             KeyCode::Char('z') | KeyCode::Char('Z') => {
-                if self.state == GameState::Play {
-                    self.board.flag(self.cursor.0, self.cursor.1)
+                if self.state == GameState::Play && !self.option_menu.state.strict_no_flags.is_on()
+                {
+                    match self.option_menu.state.flag_key {
+                        FlagKeyOption::Flag => self.board.flag(self.cursor.0, self.cursor.1),
+                        FlagKeyOption::Cycle => self.board.cycle_mark(self.cursor.0, self.cursor.1),
+                    }
+                    self.clicks += 1;
                 }
                 if self.board.game_won() {
-                    self.state = GameState::Win;
-                    self.win_menu.time = self.start_time.elapsed().as_secs();
+                    self.win();
                 }
             }
+            KeyCode::Char('?') => self.toggle_mark(Mark::Question),
+            KeyCode::Char('h') | KeyCode::Char('H') if self.state == GameState::Play => {
+                self.sandbox = Some(Sandbox::new(&self.board));
+            }
+            KeyCode::Char('s') | KeyCode::Char('S') => self.toggle_mark(Mark::Safe),
             KeyCode::Char('o') | KeyCode::Char('O') => {
                 self.prev_state = Some(self.state.clone());
                 self.state = GameState::Options;
+                self.timer.pause(&*self.clock);
             }
             _ => {}
         }
     }
 
+    /// Chords the number under the cursor.
+    fn chord(&mut self) {
+        self.chords += 1;
+        self.dig(|board, (x, y)| board.chord(x, y));
+    }
+
+    /// Digs with `dig` at the cursor, then checks for a win or a loss.
+    fn dig(&mut self, dig: impl FnOnce(&mut Board, (usize, usize)) -> Result<(), ()>) {
+        let before = self.board.clone();
+        let result = dig(&mut self.board, self.cursor);
+        if self.metrics.is_none() {
+            self.metrics = Metrics::of(&self.board);
+        }
+
+        if let Err(()) = result {
+            self.lose_menu.review = self
+                .board
+                .detonated()
+                .map(|(x, y)| solver::review(&before, x, y));
+            self.lose(LoseReason::Detonated);
+        } else if self.board.game_won() {
+            self.win();
+        }
+    }
+
     fn move_cursor(&mut self, dx: i8, dy: i8) {
         if self.cursor.0 as i8 + dx >= 0
             && self.cursor.0 as i8 + dx < self.board.width as i8
@@ -231,32 +405,199 @@ impl App {
         }
     }
 
+    /// Whether this game started with more than one life.
+    fn playing_with_lives(&self) -> bool {
+        self.lives > 1
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.timer.elapsed(&*self.clock)
+    }
+
+    /// Whether the clock should run in the current state: in play, or between
+    /// boards of a time attack that is under way.
+    fn clock_running_state(&self) -> bool {
+        match self.state {
+            GameState::Play => true,
+            GameState::Start => self.mode == ModeOption::TimeAttack && self.score.0 > 0,
+            _ => false,
+        }
+    }
+
+    /// A game is under way but its clock is stopped. The board stays hidden
+    /// until play resumes. A game lost to the clock is over, not paused.
+    fn paused(&self) -> bool {
+        let playing = match self.state {
+            GameState::Play => true,
+            GameState::Options => self.prev_state == Some(GameState::Play),
+            _ => false,
+        };
+        playing && self.board.status() == GameStatus::Playing && !self.timer.is_running()
+    }
+
+    /// Time left in a countdown or time attack.
+    fn time_left(&self) -> Option<Duration> {
+        (self.mode != ModeOption::Classic).then(|| self.time_limit.saturating_sub(self.elapsed()))
+    }
+
+    /// Ends a countdown or time attack once its time is up.
+    fn check_time(&mut self) {
+        if self.timer.is_running() && self.time_left().is_some_and(|t| t.is_zero()) {
+            self.lose_menu.review = None;
+            self.board.forfeit();
+            self.lose(LoseReason::OutOfTime);
+        }
+    }
+
+    fn lose(&mut self, reason: LoseReason) {
+        self.state = GameState::Lose;
+        self.timer.pause(&*self.clock);
+        self.sandbox = None;
+        self.lose_menu.reason = reason;
+        self.lose_menu.lives = self.lives;
+        self.lose_menu.continue_select = false;
+        self.lose_menu.score = (self.mode == ModeOption::TimeAttack).then_some(self.score);
+    }
+
+    /// Banks a cleared board in a time attack and deals the next one, with
+    /// the clock still running.
+    fn next_board(&mut self) {
+        self.score.0 += 1;
+        self.score.1 += self.metrics.map_or(0, |m| m.bbbv);
+
+        let old = &self.board;
+        let mut board = Board::new(old.width, old.height, old.mine_count);
+        board.win_rule = old.win_rule;
+        board.chord_mode = old.chord_mode;
+        board.safety = old.safety;
+        board.lives = old.lives;
+
+        self.board = board;
+        self.metrics = None;
+        self.sandbox = None;
+        self.state = GameState::Start;
+    }
+
+    fn win(&mut self) {
+        if self.mode == ModeOption::TimeAttack {
+            self.next_board();
+            return;
+        }
+
+        self.state = GameState::Win;
+        self.timer.pause(&*self.clock);
+        self.win_menu.time = self.elapsed();
+        self.win_menu.timer = self.option_menu.state.timer;
+        self.win_menu.bbbv = self.metrics.map_or(0, |m| m.bbbv);
+        self.win_menu.clicks = self.clicks;
+        self.win_menu.chords = self.chords;
+        self.win_menu.safety = self.board.safety;
+        self.win_menu.no_flags = !self.board.flags_used();
+        self.win_menu.lives = self
+            .playing_with_lives()
+            .then_some((self.board.lives, self.lives));
+    }
+
+    /// Keys that do something else while trying out a hypothesis. Returns
+    /// whether the key was used.
+    fn handle_sandbox_key(&mut self, key_event: KeyEvent) -> bool {
+        let strict = self.option_menu.state.strict_no_flags.is_on();
+        let Some(sandbox) = self.sandbox.as_mut() else {
+            return false;
+        };
+        let (x, y) = self.cursor;
+
+        match key_event.code {
+            KeyCode::Char('z') | KeyCode::Char('Z') if !strict => sandbox.toggle(x, y, Mark::Flag),
+            KeyCode::Char('s') | KeyCode::Char('S') => sandbox.toggle(x, y, Mark::Safe),
+            KeyCode::Enter => {
+                let sandbox = self.sandbox.take().unwrap();
+                self.clicks += sandbox.tentative().len() as u32;
+                sandbox.commit(&mut self.board);
+                if self.board.game_won() {
+                    self.win();
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('H') => self.sandbox = None,
+            // Nothing is dug or marked for real inside a hypothesis.
+            KeyCode::Char('x')
+            | KeyCode::Char('X')
+            | KeyCode::Char('c')
+            | KeyCode::Char('C')
+            | KeyCode::Char('z')
+            | KeyCode::Char('Z')
+            | KeyCode::Char('?') => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Puts `mark` on the tile under the cursor, or takes it off if it is
+    /// already there.
+    fn toggle_mark(&mut self, mark: Mark) {
+        if self.state != GameState::Play {
+            return;
+        }
+        let (x, y) = self.cursor;
+        let mark = if self.board.mark(x, y) == mark {
+            Mark::None
+        } else {
+            mark
+        };
+        self.board.set_mark(x, y, mark);
+        self.clicks += 1;
+    }
+
     fn quit(&mut self) {
         self.quit = true;
     }
 
+    /// The board being played, or the hypothesis branch while there is one.
+    fn shown_board(&self) -> &Board {
+        self.sandbox.as_ref().map_or(&self.board, |s| s.board())
+    }
+
     fn styled_board(&self) -> Vec<Line<'_>> {
         let mut lines: Vec<Line<'_>> = vec![];
+        let board = self.shown_board();
+        let neighbours = self.option_menu.state.theme_data.neighbours;
 
-        for y in 0..self.board.height {
+        for y in 0..board.height {
             let mut span_vec: Vec<Span> = vec![];
-            for x in 0..self.board.width {
-                if (x, y) == self.cursor
+            for x in 0..board.width {
+                let span = if self.state == GameState::Lose {
+                    self.post_mortem_span((x, y))
+                } else if (x, y) == self.cursor
                     && (self.state == GameState::Start || self.state == GameState::Play)
                 {
-                    span_vec.push(self.ts_span_cursor(self.board.check(x, y)));
+                    self.ts_span_cursor(board.check(x, y), board.mark(x, y))
+                } else if let Some(span) = self
+                    .sandbox_span((x, y))
+                    .or_else(|| self.number_span(board, (x, y)))
+                {
+                    span
+                } else if board.is_exploded(x, y) {
+                    self.exploded_span()
                 } else {
-                    span_vec.push(self.ts_span(self.board.check(x, y)));
+                    self.ts_span(board.check(x, y), board.mark(x, y))
+                };
+
+                match span.style.bg {
+                    None if self.is_neighbour((x, y)) => span_vec.push(span.bg(neighbours)),
+                    _ => span_vec.push(span),
                 }
 
                 if x < self.board.width - 1 {
-                    span_vec.push(Span::raw(" "));
+                    // Fill the gap too, so the neighbours read as one block.
+                    if self.is_neighbour((x, y)) && self.is_neighbour((x + 1, y)) {
+                        span_vec.push(Span::raw(" ").bg(neighbours));
+                    } else {
+                        span_vec.push(Span::raw(" "));
+                    }
                 }
             }
-            if self.option_menu.state.theme_data.tile_bg.is_some() {
-                lines.push(
-                    Line::from(span_vec).bg(self.option_menu.state.theme_data.tile_bg.unwrap()),
-                );
+            if let Some(bg) = self.option_menu.state.theme_data.tile_bg {
+                lines.push(Line::from(span_vec).bg(bg));
             } else {
                 lines.push(Line::from(span_vec));
             }
@@ -265,10 +606,86 @@ impl App {
         lines
     }
 
-    fn ts_span_cursor(&self, ts: TileState) -> Span<'static> {
+    /// The revealed number under the cursor, whose neighbours are picked out.
+    fn focused_number(&self) -> Option<(usize, usize)> {
+        let (x, y) = self.cursor;
+        match (&self.state, self.shown_board().check(x, y)) {
+            (GameState::Play, TileState::Count(_)) => Some((x, y)),
+            _ => None,
+        }
+    }
+
+    /// Whether `(x, y)` is one of the tiles around the focused number.
+    fn is_neighbour(&self, (x, y): (usize, usize)) -> bool {
+        self.focused_number().is_some_and(|(cx, cy)| {
+            (x, y) != (cx, cy) && x.abs_diff(cx) <= 1 && y.abs_diff(cy) <= 1
+        })
+    }
+
+    /// Tentative marks, while in a hypothesis.
+    fn sandbox_span(&self, (x, y): (usize, usize)) -> Option<Span<'static>> {
+        let sandbox = self.sandbox.as_ref()?;
+        if !sandbox.is_tentative(x, y) {
+            return None;
+        }
+
+        let glyph = match sandbox.board().mark(x, y) {
+            Mark::Safe => "○",
+            _ => "◄",
+        };
+        Some(Span::styled(
+            glyph,
+            Style::default().fg(self.option_menu.state.theme_data.tentative),
+        ))
+    }
+
+    /// Numbers picked out by the marks around them. Contradicted ones show in a
+    /// hypothesis or with "Check marks" on, and ones ready to chord only with
+    /// "Check marks".
+    fn number_span(&self, board: &Board, (x, y): (usize, usize)) -> Option<Span<'static>> {
+        let TileState::Count(n) = board.check(x, y) else {
+            return None;
+        };
+        let check = self.option_menu.state.check_marks.is_on();
+        let theme = &self.option_menu.state.theme_data;
+
+        let bg = match board.number_status(x, y)? {
+            status if status.is_contradiction() && (check || self.sandbox.is_some()) => theme.mine,
+            NumberStatus::Satisfied if check && board.can_chord(x, y) => theme.safe_mark,
+            _ => return None,
+        };
+        Some(Span::styled(
+            n.to_string(),
+            Style::default().fg(Color::Black).bg(bg),
+        ))
+    }
+
+    /// Blank rows the size of the board, so nothing can be studied while the
+    /// clock is stopped.
+    fn paused_board(&self) -> Vec<Line<'_>> {
+        (0..self.board.height)
+            .map(|y| {
+                let line = if y == self.board.height / 2 {
+                    Line::from("Paused").fg(self.option_menu.state.theme_data.tile_fg)
+                } else {
+                    Line::raw("")
+                };
+                match self.option_menu.state.theme_data.tile_bg {
+                    Some(bg) => line.centered().bg(bg),
+                    None => line.centered(),
+                }
+            })
+            .collect()
+    }
+
+    fn ts_span_cursor(&self, ts: TileState, mark: Mark) -> Span<'static> {
         match ts {
             minesweeper::TileState::Hidden => Span::styled(
-                "◼",
+                match mark {
+                    Mark::Question => "?",
+                    Mark::Safe => "○",
+                    _ => "◼",
+                },
                 Style::default()
                     .fg(Color::Black)
                     .bg(self.option_menu.state.theme_data.cursor),
@@ -285,9 +702,12 @@ impl App {
                     .fg(Color::Black)
                     .bg(self.option_menu.state.theme_data.cursor),
             ),
-            minesweeper::TileState::Mine => {
-                Span::styled("◉", Style::default().fg(Color::Black).bg(Color::Red))
-            }
+            minesweeper::TileState::Mine => Span::styled(
+                "◉",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(self.option_menu.state.theme_data.mine),
+            ),
             minesweeper::TileState::Count(n) => Span::styled(
                 n.to_string(),
                 Style::default()
@@ -297,39 +717,109 @@ impl App {
         }
     }
 
-    fn ts_span(&self, ts: TileState) -> Span<'static> {
+    fn ts_span(&self, ts: TileState, mark: Mark) -> Span<'static> {
         match ts {
+            minesweeper::TileState::Hidden if mark == Mark::Question => Span::styled(
+                "?",
+                Style::default().fg(self.option_menu.state.theme_data.question),
+            ),
+            minesweeper::TileState::Hidden if mark == Mark::Safe => Span::styled(
+                "○",
+                Style::default().fg(self.option_menu.state.theme_data.safe_mark),
+            ),
             minesweeper::TileState::Hidden => Span::styled(
                 "◼",
                 Style::default().fg(self.option_menu.state.theme_data.tile_fg),
             ),
             minesweeper::TileState::Flagged => Span::styled(
                 "◄",
-                Style::default().fg(self.option_menu.state.theme_data.flag),
+                Style::default()
+                    .fg(self.option_menu.state.theme_data.flag)
+                    .add_modifier(self.modifier(self.option_menu.state.theme_data.flag_modifier)),
             ),
             minesweeper::TileState::Empty => Span::styled(
                 "·",
                 Style::default().fg(self.option_menu.state.theme_data.tile_fg),
             ),
-            minesweeper::TileState::Mine => Span::styled("◉", Style::default().fg(Color::Red)),
-            minesweeper::TileState::Count(n) => {
-                Span::styled(n.to_string(), Style::default().fg(number_colors(n)))
+            minesweeper::TileState::Mine => Span::styled(
+                "◉",
+                Style::default().fg(self.option_menu.state.theme_data.mine),
+            ),
+            minesweeper::TileState::Count(n) => Span::styled(
+                n.to_string(),
+                Style::default()
+                    .fg(self.option_menu.state.theme_data.number(n))
+                    .add_modifier(
+                        self.modifier(self.option_menu.state.theme_data.number_modifier(n)),
+                    ),
+            ),
+        }
+    }
+
+    /// A mine that has gone off.
+    fn exploded_span(&self) -> Span<'static> {
+        Span::styled(
+            "◉",
+            Style::default()
+                .fg(Color::Black)
+                .bg(self.option_menu.state.theme_data.mine),
+        )
+    }
+
+    /// Also marks the safe tile the player could have dug instead, if any.
+    fn post_mortem_span(&self, position: (usize, usize)) -> Span<'static> {
+        let theme = &self.option_menu.state.theme_data;
+        let safe_tile = self.lose_menu.review.and_then(|r| r.safe_tile);
+        let (x, y) = position;
+        let Some(tile) = self.board.post_mortem(x, y) else {
+            return self.ts_span(self.board.check(x, y), self.board.mark(x, y));
+        };
+        match tile {
+            PostMortemTile::Detonated | PostMortemTile::Exploded => self.exploded_span(),
+            PostMortemTile::Mine => Span::styled("◉", Style::default().fg(theme.mine)),
+            PostMortemTile::Flagged => Span::styled(
+                "◄",
+                Style::default()
+                    .fg(theme.flag)
+                    .add_modifier(self.modifier(theme.flag_modifier)),
+            ),
+            PostMortemTile::WrongFlag => Span::styled(
+                "×",
+                Style::default().fg(theme.mine).add_modifier(Modifier::BOLD),
+            ),
+            PostMortemTile::Seen(TileState::Hidden) if Some(position) == safe_tile => {
+                Span::styled("◼", Style::default().fg(Color::Black).bg(theme.cursor))
             }
+            PostMortemTile::Seen(ts) => self.ts_span(ts, self.board.mark(x, y)),
         }
     }
-}
 
-fn number_colors(n: u8) -> Color {
-    // this is a fn because color does not implement Sized
-    match n {
-        1 => Color::Indexed(39),
-        2 => Color::Indexed(48),
-        3 => Color::Indexed(175),
-        4 => Color::Indexed(105),
-        5 => Color::Indexed(162),
-        6 => Color::Indexed(31),
-        7 => Color::Indexed(255),
-        _ => Color::Indexed(244),
+    /// Time played, or in a countdown or time attack, the time left: bold,
+    /// and turning to warning colors as it runs low.
+    fn timer_title(&self) -> Line<'static> {
+        let theme = &self.option_menu.state.theme_data;
+        let format = |time| format!(" {} ", self.option_menu.state.timer.format(time, 1));
+        let Some(left) = self.time_left() else {
+            return Line::from(format(self.elapsed()));
+        };
+
+        let style = Style::default().add_modifier(Modifier::BOLD);
+        let style = if left <= self.time_limit / 10 {
+            style.fg(theme.mine)
+        } else if left <= self.time_limit / 4 {
+            style.fg(theme.question)
+        } else {
+            style
+        };
+        Line::from(Span::styled(format(left), style))
+    }
+
+    fn modifier(&self, modifier: Modifier) -> Modifier {
+        if self.option_menu.state.modifiers.is_on() {
+            modifier
+        } else {
+            Modifier::empty()
+        }
     }
 }
 
@@ -343,7 +833,7 @@ fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
 
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let inner_block = Block::bordered()
+        let mut inner_block = Block::bordered()
             .border_set(border::THICK)
             .title_top(Span::styled(
                 " Minesweeper ",
@@ -352,24 +842,147 @@ impl Widget for &App {
             .title(
                 Line::from(format!(
                     " {}/{} ",
-                    self.board.mines_left, self.board.mine_count
+                    self.shown_board().mines_left,
+                    self.board.mine_count
                 ))
                 .centered(),
             )
-            .title(
-                Line::from(match self.state {
-                    GameState::Play => self.start_time.elapsed().as_secs().to_string(),
-                    _ => String::from("0"),
-                })
-                .right_aligned(),
-            )
+            .title(self.timer_title().right_aligned())
             .title_bottom(Line::from(" Quit [q] ").left_aligned())
             .title_bottom(Line::from(" Options [o] ").right_aligned())
-            .border_style(Style::new().fg(Color::White))
+            .border_style(Style::new().fg(self.option_menu.state.theme_data.border))
             .padding(Padding::symmetric(3, 1));
 
-        Paragraph::new(self.styled_board())
-            .block(inner_block)
-            .render(area, buf);
+        if self.mode == ModeOption::TimeAttack {
+            inner_block = inner_block.title(Line::from(format!(" Score {} ", self.score.1)));
+        } else if let Some(metrics) = self.metrics {
+            inner_block = inner_block.title(Line::from(format!(" 3BV {} ", metrics.bbbv)));
+        }
+        if self.playing_with_lives() {
+            inner_block = inner_block.title(Line::from(
+                format!(" ♥ {} ", self.board.lives).fg(self.option_menu.state.theme_data.mine),
+            ));
+        }
+        if self.sandbox.is_some() {
+            inner_block =
+                inner_block.title_bottom(Line::from(" Keep [enter] Drop [esc] ").centered());
+        } else if let Some((x, y)) = self.focused_number() {
+            let board = self.shown_board();
+            if let TileState::Count(n) = board.check(x, y) {
+                let around = board.surroundings(x, y);
+                inner_block = inner_block.title_bottom(
+                    Line::from(format!(
+                        " {} needed / {} flagged / {} hidden ",
+                        n, around.flags, around.hidden
+                    ))
+                    .centered(),
+                );
+            }
+        }
+        if self.state == GameState::Lose && self.lose_menu.post_mortem {
+            inner_block = inner_block.title_bottom(Line::from(" Menu [v] ").centered());
+        }
+
+        let board = if self.paused() {
+            self.paused_board()
+        } else {
+            self.styled_board()
+        };
+
+        Paragraph::new(board).block(inner_block).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use minesweeper::{clock::FakeClock, option_menu::TimeLimitOption};
+
+    use super::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    fn app(options: OptionState) -> (App, Rc<FakeClock>) {
+        let clock = Rc::new(FakeClock::new());
+        (App::new(options, clock.clone(), (80, 24)), clock)
+    }
+
+    fn press(app: &mut App, c: char) {
+        app.handle_event(Event::Key(KeyEvent::new(
+            KeyCode::Char(c),
+            KeyModifiers::empty(),
+        )));
+    }
+
+    #[test]
+    fn max_size_stays_on_the_board() {
+        let options = OptionState {
+            board_size: SizeOption::Max,
+            ..Default::default()
+        };
+        let clock = Rc::new(FakeClock::new());
+        for terminal in [(1000, 500), (2, 2)] {
+            let app = App::new(options.clone(), clock.clone(), terminal);
+            assert!((1..=Board::MAX_SIZE).contains(&app.board.width));
+            assert!((1..=Board::MAX_SIZE).contains(&app.board.height));
+        }
+    }
+
+    #[test]
+    fn options_pause_the_clock() {
+        let (mut app, clock) = app(OptionState::default());
+        press(&mut app, 'x');
+        clock.advance(secs(5));
+
+        press(&mut app, 'o');
+        assert_eq!(app.state, GameState::Options);
+        clock.advance(secs(10));
+        assert_eq!(app.elapsed(), secs(5));
+        assert!(app.paused());
+
+        press(&mut app, 'c');
+        assert_eq!(app.state, GameState::Play);
+        clock.advance(secs(2));
+        assert_eq!(app.elapsed(), secs(7));
+    }
+
+    #[test]
+    fn losing_focus_pauses_the_clock() {
+        let (mut app, clock) = app(OptionState::default());
+        press(&mut app, 'x');
+        clock.advance(secs(3));
+
+        app.handle_event(Event::FocusLost);
+        clock.advance(secs(10));
+        assert_eq!(app.elapsed(), secs(3));
+        assert!(app.paused());
+
+        app.handle_event(Event::FocusGained);
+        clock.advance(secs(1));
+        assert_eq!(app.elapsed(), secs(4));
+        assert!(!app.paused());
+    }
+
+    #[test]
+    fn countdown_runs_out() {
+        let (mut app, clock) = app(OptionState {
+            mode: ModeOption::Countdown,
+            time_limit: TimeLimitOption::One,
+            ..Default::default()
+        });
+        press(&mut app, 'x');
+
+        clock.advance(secs(59));
+        app.check_time();
+        assert_eq!(app.state, GameState::Play);
+        assert_eq!(app.time_left(), Some(secs(1)));
+
+        clock.advance(secs(1));
+        app.check_time();
+        assert_eq!(app.state, GameState::Lose);
+        assert_eq!(app.lose_menu.reason, LoseReason::OutOfTime);
+        assert_eq!(app.board.status(), GameStatus::Lost);
+        assert!(!app.paused());
     }
 }
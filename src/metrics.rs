@@ -0,0 +1,163 @@
+//! Standard measures of how much work a board is, from the mine layout alone.
+//!
+//! An *opening* is a connected patch of tiles with no neighbouring mines; one
+//! click clears it along with the numbers around its edge. Numbers that touch
+//! no opening have to be clicked one at a time. *3BV* (Bechtel's Board
+//! Benchmark Value) counts both, giving the fewest clicks that clear the board.
+
+use alloc::{vec, vec::Vec};
+
+use crate::Board;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// The fewest clicks that clear the board, without flags or chords.
+    pub bbbv: u32,
+    pub openings: u32,
+    /// Connected patches of numbers that touch no opening.
+    pub islands: u32,
+}
+
+impl Metrics {
+    /// `None` until the first dig places the mines.
+    pub fn of(board: &Board) -> Option<Self> {
+        if !board.is_generated() {
+            return None;
+        }
+
+        let (width, height) = (board.width, board.height);
+        let index = |x: usize, y: usize| y * width + x;
+        let tile = |x: usize, y: usize| &board.grid[y][x];
+
+        // Number tiles that some opening will clear.
+        let mut cleared = vec![false; width * height];
+        let mut seen = vec![false; width * height];
+        let mut openings = 0;
+
+        for (x, y) in tiles(width, height) {
+            let t = tile(x, y);
+            if t.mine || t.count != 0 || seen[index(x, y)] {
+                continue;
+            }
+            openings += 1;
+            flood(board, (x, y), &mut seen, |nx, ny| {
+                let n = tile(nx, ny);
+                cleared[index(nx, ny)] = true;
+                !n.mine && n.count == 0
+            });
+        }
+
+        let lone = |x: usize, y: usize| {
+            let t = tile(x, y);
+            !t.mine && t.count != 0 && !cleared[index(x, y)]
+        };
+        let mut seen = vec![false; width * height];
+        let mut singles = 0;
+        let mut islands = 0;
+
+        for (x, y) in tiles(width, height) {
+            if !lone(x, y) || seen[index(x, y)] {
+                continue;
+            }
+            islands += 1;
+            flood(board, (x, y), &mut seen, |nx, ny| {
+                let lone = lone(nx, ny);
+                if lone {
+                    singles += 1;
+                }
+                lone
+            });
+        }
+
+        Some(Self {
+            bbbv: openings + singles,
+            openings,
+            islands,
+        })
+    }
+}
+
+fn tiles(width: usize, height: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+}
+
+/// Visits every tile reachable from `start` through tiles `spread` returns
+/// true for. `spread` is called once on each tile reached, `start` included.
+fn flood(
+    board: &Board,
+    start: (usize, usize),
+    seen: &mut [bool],
+    mut spread: impl FnMut(usize, usize) -> bool,
+) {
+    let width = board.width;
+    seen[start.1 * width + start.0] = true;
+    let mut stack: Vec<(usize, usize)> = vec![start];
+
+    while let Some((x, y)) = stack.pop() {
+        if !spread(x, y) {
+            continue;
+        }
+
+        for (nx, ny) in neighbours(x, y, width, board.height) {
+            if !seen[ny * width + nx] {
+                seen[ny * width + nx] = true;
+                stack.push((nx, ny));
+            }
+        }
+    }
+}
+
+fn neighbours(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    let xs = x.saturating_sub(1)..=(x + 1).min(width - 1);
+    xs.flat_map(move |nx| (y.saturating_sub(1)..=(y + 1).min(height - 1)).map(move |ny| (nx, ny)))
+        .filter(move |&n| n != (x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_until_the_mines_are_placed() {
+        assert_eq!(Metrics::of(&Board::with_seed(9, 9, 10, 1)), None);
+    }
+
+    #[test]
+    fn a_number_between_two_openings_is_cleared_once() {
+        // (1, 1) is a 2 next to both the opening at (0, 0) and the one at
+        // (2, 2), which do not touch each other.
+        let board = Board::from_rows(&["##*", "###", "*##"]);
+        let metrics = Metrics::of(&board).unwrap();
+
+        assert_eq!(metrics.openings, 2);
+        assert_eq!(metrics.islands, 0);
+        assert_eq!(metrics.bbbv, 2);
+    }
+
+    #[test]
+    fn a_number_no_opening_reaches_is_its_own_click() {
+        // The 2 at (2, 1) sits between the mines, walled off from the
+        // openings on either side by other numbers.
+        let board = Board::from_rows(&["##*##", "#####", "##*##"]);
+        let metrics = Metrics::of(&board).unwrap();
+
+        assert_eq!(metrics.openings, 2);
+        assert_eq!(metrics.islands, 1);
+        assert_eq!(metrics.bbbv, 3);
+    }
+
+    #[test]
+    fn numbers_with_no_opening_form_one_island() {
+        let board = Board::from_rows(&["*#*", "###", "*#*"]);
+        let metrics = Metrics::of(&board).unwrap();
+
+        assert_eq!(metrics.openings, 0);
+        assert_eq!(metrics.islands, 1);
+        assert_eq!(metrics.bbbv, 5);
+    }
+}
@@ -1,6 +1,5 @@
-use std::{cmp::min, fmt::Display};
+use std::{cmp::min, fmt::Display, time::Duration};
 
-use color_eyre::owo_colors::OwoColorize;
 use crossterm::event::{KeyCode, KeyEvent};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
@@ -10,27 +9,279 @@ use ratatui::{
     prelude::Constraint,
     style::{Color, Modifier, Style, Styled},
     text::{Line, Span},
-    widgets::{Block, BorderType, Clear, Padding, Paragraph, Widget},
+    widgets::{Block, Clear, Padding, Paragraph, Widget},
 };
 use strum::{Display, EnumIter, IntoEnumIterator};
 
+use crate::{
+    color_depth::ColorDepth, theme::ThemeData, ChordMode, GameConfig, SafetyPolicy, WinRule,
+};
+
 #[derive(Debug)]
 pub struct OptionMenu {
     cursor_line: u32,
     pub state: OptionState,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct OptionState {
     pub board_size: SizeOption,
+    /// The board `SizeOption::Max` fills, set from the terminal.
+    pub max_size: (usize, usize),
     pub difficulty: DifficultyOption,
-    pub theme: Theme,
+    pub theme: usize,
+    pub themes: Vec<ThemeData>,
     pub theme_data: ThemeData,
+    pub modifiers: Toggle,
+    pub timer: TimerOption,
+    pub win_rule: WinRuleOption,
+    /// Turns flagging off altogether.
+    pub strict_no_flags: Toggle,
+    pub flag_key: FlagKeyOption,
+    /// Highlight numbers the flags contradict, and ones ready to chord.
+    pub check_marks: Toggle,
+    pub chording: ChordOption,
+    /// Takes effect from the next game, no wider than
+    /// [`OptionState::safety_policy`] allows.
+    pub safety: SafetyPolicy,
+    /// Takes effect from the next game.
+    pub lives: LivesOption,
+    /// Takes effect from the next game.
+    pub mode: ModeOption,
+    /// How long countdown and time-attack games last.
+    pub time_limit: TimeLimitOption,
     pub restart: bool,
     pub resume: bool,
     pub quit: bool,
 }
 
+impl OptionState {
+    /// Built-in themes followed by `custom`, all mapped to `depth`.
+    pub fn with_themes(custom: Vec<ThemeData>, depth: ColorDepth) -> Self {
+        let mut state = Self::default();
+        state.themes.extend(custom);
+        state.themes = state.themes.iter().map(|t| t.with_depth(depth)).collect();
+        state.theme_data = state.themes[state.theme].clone();
+        state
+    }
+
+    /// The chosen chord mode, except that "Strict no flags" keeps chords from
+    /// placing flags.
+    pub fn chord_mode(&self) -> ChordMode {
+        match self.chording.mode() {
+            ChordMode::ChordFlag if self.strict_no_flags.is_on() => ChordMode::OnDig,
+            mode => mode,
+        }
+    }
+
+    /// Width and height of the chosen board size.
+    pub fn dimensions(&self) -> (usize, usize) {
+        self.board_size.dimensions().unwrap_or(self.max_size)
+    }
+
+    /// The first-dig policies on offer for the chosen board: radii only go as
+    /// wide as [`SafetyPolicy::max_mines`] leaves room for the difficulty's
+    /// mines.
+    pub fn safety_choices(&self) -> Vec<SafetyPolicy> {
+        let mut choices = vec![
+            SafetyPolicy::None,
+            SafetyPolicy::FirstTile,
+            SafetyPolicy::Opening,
+        ];
+        choices.extend((1..=self.widest_radius()).map(SafetyPolicy::Radius));
+        choices
+    }
+
+    /// The chosen first-dig policy, its radius narrowed to fit the chosen board.
+    pub fn safety_policy(&self) -> SafetyPolicy {
+        match self.safety {
+            SafetyPolicy::Radius(r) => match self.widest_radius() {
+                0 => SafetyPolicy::FirstTile,
+                widest => SafetyPolicy::Radius(r.min(widest)),
+            },
+            policy => policy,
+        }
+    }
+
+    fn widest_radius(&self) -> u8 {
+        let (width, height) = self.dimensions();
+        SafetyPolicy::widest_radius(width, height, self.difficulty.mines(width, height))
+    }
+}
+
+impl Default for OptionState {
+    fn default() -> Self {
+        Self {
+            board_size: Default::default(),
+            max_size: SizeOption::Medium.dimensions().unwrap(),
+            difficulty: Default::default(),
+            theme: 0,
+            themes: ThemeData::builtins(),
+            theme_data: Default::default(),
+            modifiers: Default::default(),
+            timer: Default::default(),
+            win_rule: Default::default(),
+            strict_no_flags: Default::default(),
+            flag_key: Default::default(),
+            check_marks: Default::default(),
+            chording: Default::default(),
+            safety: Default::default(),
+            lives: Default::default(),
+            mode: Default::default(),
+            time_limit: Default::default(),
+            restart: false,
+            resume: false,
+            quit: false,
+        }
+    }
+}
+
+#[derive(EnumIter, PartialEq, Display, Debug, Clone, Copy, Default, FromPrimitive)]
+pub enum Toggle {
+    #[default]
+    Off = 0,
+    On,
+}
+
+impl Toggle {
+    pub fn is_on(&self) -> bool {
+        *self == Toggle::On
+    }
+}
+
+/// How times are shown: to the millisecond (tenths on the live timer), or in
+/// whole seconds.
+#[derive(EnumIter, PartialEq, Display, Debug, Clone, Copy, Default, FromPrimitive)]
+pub enum TimerOption {
+    #[default]
+    Precise = 0,
+    Seconds,
+}
+
+impl TimerOption {
+    /// Formats `time` in seconds with `digits` decimal places (1 to 3),
+    /// rounding down so a running timer never shows a moment early.
+    pub fn format(&self, time: Duration, digits: u32) -> String {
+        match self {
+            TimerOption::Precise => format!(
+                "{}.{:0width$}",
+                time.as_secs(),
+                time.subsec_millis() / 10u32.pow(3 - digits),
+                width = digits as usize
+            ),
+            TimerOption::Seconds => time.as_secs().to_string(),
+        }
+    }
+}
+
+#[derive(EnumIter, PartialEq, Display, Debug, Clone, Copy, Default, FromPrimitive)]
+pub enum WinRuleOption {
+    #[default]
+    Cleared = 0,
+    Flagged,
+}
+
+impl WinRuleOption {
+    pub fn rule(&self) -> WinRule {
+        match self {
+            WinRuleOption::Cleared => WinRule::Cleared,
+            WinRuleOption::Flagged => WinRule::Flagged,
+        }
+    }
+}
+
+/// Whether the flag key only flags, or cycles through every mark.
+#[derive(EnumIter, PartialEq, Display, Debug, Clone, Copy, Default, FromPrimitive)]
+pub enum FlagKeyOption {
+    #[default]
+    Flag = 0,
+    Cycle,
+}
+
+/// When numbers chord: never, when dug, only with the chord key, or when dug
+/// with their sure mines flagged as well.
+#[derive(EnumIter, PartialEq, Display, Debug, Clone, Copy, Default, FromPrimitive)]
+pub enum ChordOption {
+    Off = 0,
+    #[default]
+    Dig,
+    Key,
+    #[strum(to_string = "Dig+flag")]
+    DigFlag,
+}
+
+impl ChordOption {
+    pub fn mode(&self) -> ChordMode {
+        match self {
+            ChordOption::Off => ChordMode::Disabled,
+            ChordOption::Dig => ChordMode::OnDig,
+            ChordOption::Key => ChordMode::Key,
+            ChordOption::DigFlag => ChordMode::ChordFlag,
+        }
+    }
+}
+
+/// How many mines can go off before the game is lost.
+#[derive(EnumIter, PartialEq, Display, Debug, Clone, Copy, Default, FromPrimitive)]
+pub enum LivesOption {
+    #[default]
+    #[strum(to_string = "1")]
+    One = 0,
+    #[strum(to_string = "2")]
+    Two,
+    #[strum(to_string = "3")]
+    Three,
+    #[strum(to_string = "5")]
+    Five,
+}
+
+impl LivesOption {
+    pub fn count(&self) -> u32 {
+        match self {
+            LivesOption::One => 1,
+            LivesOption::Two => 2,
+            LivesOption::Three => 3,
+            LivesOption::Five => 5,
+        }
+    }
+}
+
+/// Whether games race a clock: `Countdown` clears one board before the time
+/// limit, `TimeAttack` clears as many as it can, scored by their total 3BV.
+#[derive(EnumIter, PartialEq, Display, Debug, Clone, Copy, Default, FromPrimitive)]
+pub enum ModeOption {
+    #[default]
+    Classic = 0,
+    Countdown,
+    #[strum(to_string = "Time attack")]
+    TimeAttack,
+}
+
+#[derive(EnumIter, PartialEq, Display, Debug, Clone, Copy, Default, FromPrimitive)]
+pub enum TimeLimitOption {
+    #[strum(to_string = "1 min")]
+    One = 0,
+    #[default]
+    #[strum(to_string = "3 min")]
+    Three,
+    #[strum(to_string = "5 min")]
+    Five,
+    #[strum(to_string = "10 min")]
+    Ten,
+}
+
+impl TimeLimitOption {
+    pub fn duration(&self) -> Duration {
+        let minutes = match self {
+            TimeLimitOption::One => 1,
+            TimeLimitOption::Three => 3,
+            TimeLimitOption::Five => 5,
+            TimeLimitOption::Ten => 10,
+        };
+        Duration::from_secs(minutes * 60)
+    }
+}
+
 #[derive(EnumIter, PartialEq, Display, Debug, Clone, Default, FromPrimitive)]
 pub enum SizeOption {
     Small = 0,
@@ -49,54 +300,54 @@ pub enum DifficultyOption {
     Expert,
 }
 
-#[derive(Debug, Clone)]
-pub struct ThemeData {
-    pub cursor: Color,
-    pub flag: Color,
-    pub tile_fg: Color,
-    pub tile_bg: Option<Color>,
+impl SizeOption {
+    /// Width and height in tiles. `Max` depends on the terminal, so has none.
+    pub fn dimensions(&self) -> Option<(usize, usize)> {
+        match self {
+            SizeOption::Small => Some((15, 10)),
+            SizeOption::Medium => Some((24, 16)),
+            SizeOption::Large => Some((48, 32)),
+            SizeOption::Max => None,
+        }
+    }
 }
 
-// TODO add flag, dot
-impl ThemeData {
-    fn new(theme: &Theme) -> Self {
-        match theme {
-            Theme::Default => Self {
-                cursor: Color::Indexed(190),
-                flag: Color::Rgb(227, 85, 85),
-                tile_fg: Color::Gray,
-                tile_bg: None,
-            },
-            Theme::Light => Self {
-                cursor: Color::Indexed(190),
-                flag: Color::Rgb(227, 85, 85),
-                tile_fg: Color::Rgb(233, 233, 233),
-                tile_bg: Some(Color::Rgb(205, 205, 205)),
-            },
-            Theme::Dark => Self {
-                cursor: Color::Rgb(56, 132, 133),
-                flag: Color::Rgb(227, 123, 87),
-                tile_fg: Color::Rgb(70, 70, 70),
-                tile_bg: Some(Color::Rgb(44, 44, 44)),
-            },
+impl DifficultyOption {
+    /// The share of tiles that are mines.
+    pub fn density(&self) -> f32 {
+        match self {
+            DifficultyOption::Easy => 0.12,
+            DifficultyOption::Medium => 0.15,
+            DifficultyOption::Hard => 0.17,
+            DifficultyOption::Expert => 0.20,
         }
     }
-}
 
-impl Default for ThemeData {
-    fn default() -> Self {
-        Self::new(&Theme::Default)
+    /// Mines for a `width` by `height` board at this density.
+    pub fn mines(&self, width: usize, height: usize) -> u32 {
+        (self.density() * (width * height) as f32) as u32
     }
-}
 
-#[derive(EnumIter, Debug, PartialEq, Display, FromPrimitive, Default, Clone)]
-pub enum Theme {
-    #[default]
-    Default = 0,
-    Light,
-    Dark,
+    /// Never more mines than `safety` leaves room for.
+    pub fn config(&self, width: usize, height: usize, safety: SafetyPolicy) -> GameConfig {
+        GameConfig {
+            width,
+            height,
+            mines: self
+                .mines(width, height)
+                .min(safety.max_mines(width, height)),
+            safety,
+        }
+    }
 }
 
+/// Rows of settings, above the buttons.
+const OPTIONS: u32 = 14;
+const RESTART: u32 = OPTIONS;
+const CONTINUE: u32 = OPTIONS + 1;
+/// Fits the settings, both buttons, the gaps around them and the border.
+pub const MENU_HEIGHT: u16 = OPTIONS as u16 + 8;
+
 impl OptionMenu {
     pub fn new(options: OptionState) -> Self {
         Self {
@@ -106,59 +357,57 @@ impl OptionMenu {
     }
 
     fn update_theme(&mut self) {
-        self.state.theme_data = ThemeData::new(&self.state.theme);
+        self.state.theme_data = self.state.themes[self.state.theme].clone();
+    }
+
+    fn change(&mut self, step: i32) {
+        match self.cursor_line {
+            0 => self.state.board_size = cycle(self.state.board_size.clone() as u32, step),
+            1 => self.state.difficulty = cycle(self.state.difficulty.clone() as u32, step),
+            2 => {
+                let n = self.state.themes.len() as i32;
+                self.state.theme = (self.state.theme as i32 + step).rem_euclid(n) as usize;
+                self.update_theme();
+            }
+            3 => self.state.modifiers = cycle(self.state.modifiers as u32, step),
+            4 => self.state.timer = cycle(self.state.timer as u32, step),
+            5 => self.state.win_rule = cycle(self.state.win_rule as u32, step),
+            6 => self.state.strict_no_flags = cycle(self.state.strict_no_flags as u32, step),
+            7 => self.state.flag_key = cycle(self.state.flag_key as u32, step),
+            8 => self.state.check_marks = cycle(self.state.check_marks as u32, step),
+            9 => self.state.chording = cycle(self.state.chording as u32, step),
+            10 => {
+                let choices = self.state.safety_choices();
+                let n = choices.len() as i32;
+                let i = choices
+                    .iter()
+                    .position(|&p| p == self.state.safety_policy())
+                    .unwrap_or(0) as i32;
+                self.state.safety = choices[(i + step).rem_euclid(n) as usize];
+            }
+            11 => self.state.lives = cycle(self.state.lives as u32, step),
+            12 => self.state.mode = cycle(self.state.mode as u32, step),
+            13 => self.state.time_limit = cycle(self.state.time_limit as u32, step),
+            _ => (),
+        }
     }
 
     pub fn handle_key_event(&mut self, key_event: KeyEvent) {
         match key_event.code {
-            KeyCode::Left => match self.cursor_line {
-                0 => {
-                    self.state.board_size =
-                        FromPrimitive::from_u32((self.state.board_size.clone() as u32 + 4) % 5)
-                            .unwrap()
-                }
-                1 => {
-                    self.state.difficulty =
-                        FromPrimitive::from_u32((self.state.difficulty.clone() as u32 + 4) % 5)
-                            .unwrap()
-                }
-                2 => {
-                    self.state.theme =
-                        FromPrimitive::from_u32((self.state.theme.clone() as u32 + 2) % 3).unwrap();
-                    self.update_theme();
-                }
-                _ => (),
-            },
-            KeyCode::Right => match self.cursor_line {
-                0 => {
-                    self.state.board_size =
-                        FromPrimitive::from_u32((self.state.board_size.clone() as u32 + 1) % 5)
-                            .unwrap()
-                }
-                1 => {
-                    self.state.difficulty =
-                        FromPrimitive::from_u32((self.state.difficulty.clone() as u32 + 1) % 5)
-                            .unwrap()
-                }
-                2 => {
-                    self.state.theme =
-                        FromPrimitive::from_u32((self.state.theme.clone() as u32 + 1) % 3).unwrap();
-                    self.update_theme();
-                }
-                _ => (),
-            },
+            KeyCode::Left => self.change(-1),
+            KeyCode::Right => self.change(1),
             KeyCode::Up => self.cursor_line = self.cursor_line.saturating_sub(1),
-            KeyCode::Down => self.cursor_line = min(self.cursor_line + 1, 4),
+            KeyCode::Down => self.cursor_line = min(self.cursor_line + 1, CONTINUE),
             KeyCode::Char('q') | KeyCode::Char('Q') => self.state.quit = true,
             KeyCode::Char('o') | KeyCode::Char('O') | KeyCode::Char('c') | KeyCode::Char('C') => {
                 self.state.resume = true
             }
             KeyCode::Char('r') | KeyCode::Char('R') => self.state.restart = true,
             KeyCode::Char(' ') | KeyCode::Char('x') | KeyCode::Enter => match self.cursor_line {
-                3 => {
+                RESTART => {
                     self.state.restart = true;
                 }
-                4 => {
+                CONTINUE => {
                     self.state.resume = true;
                 }
                 _ => {}
@@ -168,111 +417,170 @@ impl OptionMenu {
     }
 }
 
+fn cycle<T: IntoEnumIterator + FromPrimitive>(value: u32, step: i32) -> T {
+    let n = T::iter().count() as i32;
+    T::from_i32((value as i32 + step).rem_euclid(n)).unwrap()
+}
+
 impl Widget for &OptionMenu {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let layout = Layout::vertical([
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Length(1),
+        let theme = &self.state.theme_data;
+        let safety_choices = self.state.safety_choices();
+        let rows = [
+            SingleSelector::from_enum("Board size:", self.state.board_size.clone(), theme),
+            SingleSelector::from_enum("Difficulty:", self.state.difficulty.clone(), theme),
+            SingleSelector::new(
+                "Theme:",
+                self.state.themes.iter().map(|t| t.name.clone()).collect(),
+                self.state.theme,
+                theme,
+            ),
+            SingleSelector::from_enum("Modifiers:", self.state.modifiers, theme),
+            SingleSelector::from_enum("Timer:", self.state.timer, theme),
+            SingleSelector::from_enum("Win rule:", self.state.win_rule, theme),
+            SingleSelector::from_enum("Strict no flags:", self.state.strict_no_flags, theme),
+            SingleSelector::from_enum("Flag key:", self.state.flag_key, theme),
+            SingleSelector::from_enum("Check marks:", self.state.check_marks, theme),
+            SingleSelector::from_enum("Chording:", self.state.chording, theme),
+            SingleSelector::new(
+                "First dig:",
+                safety_choices.iter().map(|p| p.to_string()).collect(),
+                safety_choices
+                    .iter()
+                    .position(|&p| p == self.state.safety_policy())
+                    .unwrap_or(0),
+                theme,
+            ),
+            SingleSelector::from_enum("Lives:", self.state.lives, theme),
+            SingleSelector::from_enum("Mode:", self.state.mode, theme),
+            SingleSelector::from_enum("Time limit:", self.state.time_limit, theme),
+        ];
+
+        let mut constraints = vec![Constraint::Length(1); rows.len()];
+        constraints.extend([
             Constraint::Fill(1),
             Constraint::Length(1),
             Constraint::Length(1),
-        ])
-        .spacing(1);
-
-        // Clear.render(area, buf);
+            Constraint::Length(1),
+        ]);
 
         let option_block = Block::bordered()
-            .set_style(
-                Style::new().fg(Color::Rgb(48, 48, 48)).bg(Color::Reset), //
-                                                                          // Style::new().fg(self.state.theme_data.tile_fg).bg(self
-                                                                          //     .state
-                                                                          //     .theme_data
-                                                                          //     .tile_bg
-                                                                          //     .unwrap_or(Color::Reset)),
-            )
-            // .border_type(BorderType::QuadrantInside)
-            .border_style(Style::new().fg(Color::White).bg(Color::Reset))
+            .set_style(Style::new().fg(theme.menu_dim).bg(theme.menu_bg))
+            .border_style(Style::new().fg(theme.border).bg(theme.menu_bg))
             .title(" Options ")
             .title_alignment(Alignment::Center)
             .padding(Padding::proportional(1));
         option_block.clone().render(area, buf);
 
-        let [size_area, difficulty_area, theme_area, _, restart_button, continue_button] =
-            layout.areas(option_block.inner(area));
+        let areas = Layout::vertical(constraints).split(option_block.inner(area));
+        let (restart_button, continue_button) = (areas[rows.len() + 1], areas[rows.len() + 3]);
 
-        SingleSelector::<SizeOption>::new(
-            String::from("Board size:"),
-            self.state.board_size.clone(),
-            self.cursor_line == 0,
-        )
-        .render(size_area, buf);
-
-        SingleSelector::<DifficultyOption>::new(
-            String::from("Difficulty:"),
-            self.state.difficulty.clone(),
-            self.cursor_line == 1,
-        )
-        .render(difficulty_area, buf);
+        for (i, (mut row, area)) in rows.into_iter().zip(areas.iter()).enumerate() {
+            row.highlight = self.cursor_line == i as u32;
+            row.render(*area, buf);
+        }
 
-        SingleSelector::<Theme>::new(
-            String::from("Theme:"),
-            self.state.theme.clone(),
-            self.cursor_line == 2,
+        Line::from(
+            Span::from("Restart?").style(if self.cursor_line == RESTART {
+                Style::default()
+                    .bg(theme.menu_highlight)
+                    .fg(theme.menu_accent)
+            } else {
+                Style::default().fg(Color::Gray)
+            }),
         )
-        .render(theme_area, buf);
-
-        Line::from(Span::from("Restart?").style(if self.cursor_line == 3 {
-            Style::default().bg(Color::DarkGray).fg(Color::LightGreen)
-        } else {
-            Style::default().fg(Color::Gray)
-        }))
         .centered()
         .render(restart_button, buf);
 
-        Line::from(Span::from("Continue").style(if self.cursor_line == 4 {
-            Style::default().bg(Color::DarkGray).fg(Color::LightGreen)
-        } else {
-            Style::default().fg(Color::Gray)
-        }))
+        Line::from(
+            Span::from("Continue").style(if self.cursor_line == CONTINUE {
+                Style::default()
+                    .bg(theme.menu_highlight)
+                    .fg(theme.menu_accent)
+            } else {
+                Style::default().fg(Color::Gray)
+            }),
+        )
         .centered()
         .render(continue_button, buf);
     }
 }
 
-#[derive(PartialEq, Default)]
-struct SingleSelector<T> {
-    label: String,
-    state: T,
+struct SingleSelector<'a> {
+    label: &'static str,
+    options: Vec<String>,
+    selected: usize,
     pub highlight: bool,
+    theme: &'a ThemeData,
 }
 
-impl<T: Default> SingleSelector<T> {
-    fn new(label: String, state: T, highlight: bool) -> Self {
+impl<'a> SingleSelector<'a> {
+    fn new(
+        label: &'static str,
+        options: Vec<String>,
+        selected: usize,
+        theme: &'a ThemeData,
+    ) -> Self {
         Self {
             label,
-            state,
-            highlight,
+            options,
+            selected,
+            highlight: false,
+            theme,
+        }
+    }
+
+    fn from_enum<T: IntoEnumIterator + Display + PartialEq>(
+        label: &'static str,
+        state: T,
+        theme: &'a ThemeData,
+    ) -> Self {
+        let selected = T::iter().position(|i| i == state).unwrap_or(0);
+        Self::new(
+            label,
+            T::iter().map(|i| i.to_string()).collect(),
+            selected,
+            theme,
+        )
+    }
+
+    /// The widest run of options around the selected one that fits in `width`.
+    fn visible(&self, width: usize) -> (usize, usize) {
+        let cost = |i: usize| self.options[i].len() + 3;
+        let (mut start, mut end) = (self.selected, self.selected + 1);
+        let mut used = self.label.len() + cost(self.selected);
+
+        loop {
+            if end < self.options.len() && used + cost(end) <= width {
+                used += cost(end);
+                end += 1;
+            } else if start > 0 && used + cost(start - 1) <= width {
+                start -= 1;
+                used += cost(start);
+            } else {
+                return (start, end);
+            }
         }
     }
 }
 
-impl<T: IntoEnumIterator + Display + PartialEq + FromPrimitive> Widget for SingleSelector<T> {
+impl Widget for SingleSelector<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         if self.highlight {
             Clear.render(area, buf);
-            buf.set_style(area, Style::new().bg(Color::DarkGray));
+            buf.set_style(area, Style::new().bg(self.theme.menu_highlight));
         }
 
-        let style_base = Style::default().fg(Color::White);
+        let style_base = Style::default().fg(self.theme.menu_fg);
+        let (start, end) = self.visible(area.width as usize);
 
         let mut constraints = vec![
             Constraint::Length(self.label.len() as u16),
             Constraint::Fill(1),
         ];
 
-        for i in T::iter() {
-            constraints.push(Constraint::Length(i.to_string().len() as u16 + 2));
+        for option in &self.options[start..end] {
+            constraints.push(Constraint::Length(option.len() as u16 + 2));
         }
 
         let areas = Layout::new(Direction::Horizontal, constraints)
@@ -283,18 +591,16 @@ impl<T: IntoEnumIterator + Display + PartialEq + FromPrimitive> Widget for Singl
             .style(style_base)
             .render(areas[0], buf);
 
-        let mut count = 2;
-        for i in T::iter() {
-            Paragraph::new(format!("<{}>", i.to_string()))
-                .style(if self.state == i {
+        for (i, area) in (start..end).zip(areas.iter().skip(2)) {
+            Paragraph::new(format!("<{}>", self.options[i]))
+                .style(if self.selected == i {
                     style_base
                         .add_modifier(Modifier::BOLD)
-                        .fg(Color::LightGreen)
+                        .fg(self.theme.menu_accent)
                 } else {
                     style_base
                 })
-                .render(areas[count], buf);
-            count += 1;
+                .render(*area, buf);
         }
     }
 }
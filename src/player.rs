@@ -0,0 +1,99 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{solver, Board, TileState};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Dig(usize, usize),
+    Flag(usize, usize),
+    Chord(usize, usize),
+}
+
+impl Action {
+    pub fn position(&self) -> (usize, usize) {
+        match *self {
+            Action::Dig(x, y) | Action::Flag(x, y) | Action::Chord(x, y) => (x, y),
+        }
+    }
+}
+
+/// Something that can play a game, one action at a time. It only gets to see
+/// what [`Board::check`] shows a human player.
+pub trait Player {
+    fn name(&self) -> String;
+
+    fn act(&mut self, board: &Board) -> Action;
+
+    /// Called before every game, so state from the last one can be dropped.
+    fn reset(&mut self) {}
+}
+
+/// Plays only moves the numbers prove, and digs the least likely mine when
+/// there are none.
+#[derive(Debug, Default)]
+pub struct SolverPlayer {
+    queue: Vec<Action>,
+}
+
+impl SolverPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Player for SolverPlayer {
+    fn name(&self) -> String {
+        String::from("solver")
+    }
+
+    fn act(&mut self, board: &Board) -> Action {
+        if !board.is_generated() {
+            return Action::Dig(board.width / 2, board.height / 2);
+        }
+
+        // Earlier deductions stay true, so only re-solve once they run out.
+        while let Some(action) = self.queue.pop() {
+            let (x, y) = action.position();
+            if let TileState::Hidden = board.check(x, y) {
+                return action;
+            }
+        }
+
+        let analysis = solver::analyze(board);
+        let unflagged = |&(x, y): &(usize, usize)| matches!(board.check(x, y), TileState::Hidden);
+
+        self.queue.extend(
+            analysis
+                .mine_tiles()
+                .into_iter()
+                .filter(unflagged)
+                .map(|(x, y)| Action::Flag(x, y)),
+        );
+        self.queue.extend(
+            analysis
+                .safe_tiles()
+                .into_iter()
+                .filter(unflagged)
+                .map(|(x, y)| Action::Dig(x, y)),
+        );
+
+        if let Some(action) = self.queue.pop() {
+            return action;
+        }
+
+        let (x, y) = (0..board.height)
+            .flat_map(|y| (0..board.width).map(move |x| (x, y)))
+            .filter(unflagged)
+            .min_by(|a, b| {
+                let pa = analysis.probability(a.0, a.1).unwrap_or(1.0);
+                let pb = analysis.probability(b.0, b.1).unwrap_or(1.0);
+                pa.total_cmp(&pb)
+            })
+            .unwrap_or((0, 0));
+        Action::Dig(x, y)
+    }
+
+    fn reset(&mut self) {
+        self.queue.clear();
+    }
+}
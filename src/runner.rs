@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    player::{Action, Player},
+    solver, Board, GameConfig, GameStatus,
+};
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub config: GameConfig,
+    pub games: u32,
+    pub wins: u32,
+    /// Time spent on the games that were won.
+    pub solve_time: Duration,
+    /// Digs made while the board had no provably safe tile left.
+    pub guesses: u32,
+}
+
+impl Report {
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.games.max(1) as f64
+    }
+
+    pub fn average_time(&self) -> Duration {
+        self.solve_time / self.wins.max(1)
+    }
+
+    pub fn average_guesses(&self) -> f64 {
+        self.guesses as f64 / self.games.max(1) as f64
+    }
+}
+
+/// Plays `games` games of `config`. Game `i` uses seed `seed + i`, so the same
+/// arguments always deal the same boards. Panics unless `config`
+/// [fits](GameConfig::fits).
+pub fn run<P: Player>(player: &mut P, config: GameConfig, games: u32, seed: u64) -> Report {
+    assert!(
+        config.fits(),
+        "can't deal a {}x{} board with {} mines",
+        config.width,
+        config.height,
+        config.mines
+    );
+    let mut report = Report {
+        config,
+        games,
+        wins: 0,
+        solve_time: Duration::ZERO,
+        guesses: 0,
+    };
+
+    for i in 0..games {
+        let mut board = config.board(seed.wrapping_add(i as u64));
+        let (status, time, guesses) = play(player, &mut board);
+
+        report.guesses += guesses;
+        if status == GameStatus::Won {
+            report.wins += 1;
+            report.solve_time += time;
+        }
+    }
+
+    report
+}
+
+/// Plays one game to the end. A player that stalls or points off the board
+/// loses.
+fn play<P: Player>(player: &mut P, board: &mut Board) -> (GameStatus, Duration, u32) {
+    player.reset();
+
+    let mut time = Duration::ZERO;
+    let mut guesses = 0;
+    // Safe tiles stay safe, so the board only needs solving again once all of
+    // the ones found last time are dug.
+    let mut known_safe: Vec<(usize, usize)> = vec![];
+
+    for _ in 0..4 * board.width * board.height {
+        if let GameStatus::Won | GameStatus::Lost = board.status() {
+            break;
+        }
+
+        let start = Instant::now();
+        let action = player.act(board);
+        time += start.elapsed();
+
+        let (x, y) = action.position();
+        if x >= board.width || y >= board.height {
+            return (GameStatus::Lost, time, guesses);
+        }
+
+        if let (Action::Dig(..), true) = (action, board.is_generated()) {
+            known_safe.retain(|&(x, y)| !board.is_revealed(x, y));
+            if known_safe.is_empty() {
+                known_safe = solver::analyze(board).safe_tiles();
+            }
+            if known_safe.is_empty() {
+                guesses += 1;
+            }
+        }
+
+        let start = Instant::now();
+        match action {
+            Action::Dig(x, y) if !board.is_generated() => {
+                let _ = board.first_dig(x, y);
+            }
+            Action::Dig(x, y) => {
+                let _ = board.dig(x, y);
+            }
+            Action::Flag(x, y) => board.flag(x, y),
+            Action::Chord(x, y) => {
+                let _ = board.chord(x, y);
+            }
+        }
+        time += start.elapsed();
+    }
+
+    match board.status() {
+        GameStatus::Won => (GameStatus::Won, time, guesses),
+        _ => (GameStatus::Lost, time, guesses),
+    }
+}
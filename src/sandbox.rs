@@ -0,0 +1,67 @@
+//! A branch of a game for trying out "what if" flags and safe marks.
+//!
+//! The branch starts as a copy of the board. Marks placed in it are kept apart
+//! as tentative, so they can be told from the real ones, and the numbers they
+//! contradict can be checked with [`Board::number_status`] on the branch before
+//! the marks are kept or thrown away.
+
+use alloc::vec::Vec;
+
+use crate::{Board, Mark};
+
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    board: Board,
+    tentative: Vec<(usize, usize)>,
+}
+
+impl Sandbox {
+    pub fn new(board: &Board) -> Self {
+        Self {
+            board: board.clone(),
+            tentative: Vec::new(),
+        }
+    }
+
+    /// The branch, tentative marks included.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Marks placed in the sandbox, in the order they were placed.
+    pub fn tentative(&self) -> &[(usize, usize)] {
+        &self.tentative
+    }
+
+    pub fn is_tentative(&self, x: usize, y: usize) -> bool {
+        self.tentative.contains(&(x, y))
+    }
+
+    /// Puts a tentative `mark` on a hidden tile, or takes it off if it is
+    /// already there. Marks from before the sandbox are left alone.
+    pub fn toggle(&mut self, x: usize, y: usize, mark: Mark) {
+        if self.board.is_revealed(x, y) {
+            return;
+        }
+
+        if self.is_tentative(x, y) {
+            if self.board.mark(x, y) == mark {
+                self.board.set_mark(x, y, Mark::None);
+                self.tentative.retain(|&t| t != (x, y));
+            } else {
+                self.board.set_mark(x, y, mark);
+            }
+        } else if self.board.mark(x, y) == Mark::None {
+            self.board.set_mark(x, y, mark);
+            self.tentative.push((x, y));
+        }
+    }
+
+    /// Copies the tentative marks onto `board`, the one the sandbox was
+    /// opened on. Nothing else about `board` changes.
+    pub fn commit(self, board: &mut Board) {
+        for &(x, y) in &self.tentative {
+            board.set_mark(x, y, self.board.mark(x, y));
+        }
+    }
+}
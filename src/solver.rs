@@ -0,0 +1,631 @@
+//! Works out what the visible board says about the hidden tiles.
+//!
+//! Only revealed numbers and the total mine count are used. Flags are the
+//! player's opinion, so flagged tiles are treated like any other hidden tile.
+//!
+//! Hidden tiles next to a number are split into independent groups, and every
+//! mine layout of each group that fits its numbers is counted. The groups are
+//! then combined, weighting each total by the number of ways the remaining
+//! mines fit in the tiles that no number touches.
+
+use alloc::{vec, vec::Vec};
+
+use crate::{Board, TileState};
+
+/// Past this many search steps a group is estimated instead of solved exactly.
+const SEARCH_LIMIT: u32 = 1_000_000;
+
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    width: usize,
+    height: usize,
+    probabilities: Vec<Option<f64>>,
+}
+
+impl Analysis {
+    /// The chance that a hidden tile is a mine, or `None` for revealed tiles.
+    pub fn probability(&self, x: usize, y: usize) -> Option<f64> {
+        self.probabilities[y * self.width + x]
+    }
+
+    /// Hidden tiles that cannot be mines.
+    pub fn safe_tiles(&self) -> Vec<(usize, usize)> {
+        self.tiles_where(|p| p == 0.0)
+    }
+
+    /// Hidden tiles that must be mines.
+    pub fn mine_tiles(&self) -> Vec<(usize, usize)> {
+        self.tiles_where(|p| p == 1.0)
+    }
+
+    /// The hidden tile least likely to be a mine.
+    pub fn safest(&self) -> Option<(usize, usize)> {
+        self.tiles_where(|_| true).into_iter().min_by(|a, b| {
+            let pa = self.probability(a.0, a.1).unwrap();
+            let pb = self.probability(b.0, b.1).unwrap();
+            pa.total_cmp(&pb)
+        })
+    }
+
+    fn tiles_where(&self, f: impl Fn(f64) -> bool) -> Vec<(usize, usize)> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.probability(x, y).is_some_and(&f))
+            .collect()
+    }
+}
+
+/// What the numbers said about a tile, judged after it went off.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    /// The tile could not have been a mine.
+    Safe,
+    /// The tile had to be a mine.
+    Mine,
+    /// Nothing settled it either way.
+    Guess,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Review {
+    pub verdict: Verdict,
+    /// The chance the tile was a mine.
+    pub probability: f64,
+    /// A tile that was provably safe instead, if there was one.
+    pub safe_tile: Option<(usize, usize)>,
+}
+
+impl Review {
+    /// A guess with no safe tile left to dig, so the loss was down to luck.
+    pub fn forced(&self) -> bool {
+        self.verdict == Verdict::Guess && self.safe_tile.is_none()
+    }
+}
+
+/// Judges a dig of `(x, y)` against `board` as it stood just before the dig.
+pub fn review(board: &Board, x: usize, y: usize) -> Review {
+    let analysis = analyze(board);
+    let probability = analysis.probability(x, y).unwrap_or(0.0);
+    let verdict = if probability == 0.0 {
+        Verdict::Safe
+    } else if probability == 1.0 {
+        Verdict::Mine
+    } else {
+        Verdict::Guess
+    };
+    let safe_tile = analysis
+        .safe_tiles()
+        .into_iter()
+        .find(|&(x, y)| matches!(board.check(x, y), TileState::Hidden));
+
+    Review {
+        verdict,
+        probability,
+        safe_tile,
+    }
+}
+
+/// A group of hidden tiles linked by shared numbers.
+struct Group {
+    tiles: Vec<usize>,
+    /// `solutions[k]` is how many layouts put `k` mines in the group.
+    solutions: Vec<f64>,
+    /// `mines[k][i]` is how many of those layouts have a mine on `tiles[i]`.
+    mines: Vec<Vec<f64>>,
+}
+
+struct Constraint {
+    tiles: Vec<usize>,
+    mines: usize,
+}
+
+pub fn analyze(board: &Board) -> Analysis {
+    let (width, height) = (board.width, board.height);
+    let mut probabilities = vec![None; width * height];
+    let mut known_mines = 0;
+    let mut unknown = vec![false; width * height];
+
+    for y in 0..height {
+        for x in 0..width {
+            match board.check(x, y) {
+                TileState::Hidden | TileState::Flagged => unknown[y * width + x] = true,
+                TileState::Mine => known_mines += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let mines_left = (board.mine_count as usize).saturating_sub(known_mines);
+
+    if !board.is_generated() {
+        let p = mines_left as f64 / (width * height) as f64;
+        probabilities.iter_mut().for_each(|t| *t = Some(p));
+        return Analysis {
+            width,
+            height,
+            probabilities,
+        };
+    }
+
+    let mut constraints = constraints(board, &unknown);
+    let mut on_frontier = vec![false; width * height];
+    constraints
+        .iter()
+        .flat_map(|c| &c.tiles)
+        .for_each(|&t| on_frontier[t] = true);
+
+    let interior = (0..width * height)
+        .filter(|&t| unknown[t] && !on_frontier[t])
+        .count();
+
+    let mut deduced_mines = 0;
+    for (t, mine) in deduce(&mut constraints, width * height) {
+        deduced_mines += mine as usize;
+        probabilities[t] = Some(if mine { 1.0 } else { 0.0 });
+    }
+
+    let mut groups = vec![];
+    let mut estimated_mines = 0.0;
+
+    for (tiles, group_constraints) in split(&constraints, width * height) {
+        match solve(&tiles, &group_constraints) {
+            Some(group) => groups.push(group),
+            None => {
+                for (t, p) in estimate(&tiles, &group_constraints) {
+                    estimated_mines += p;
+                    probabilities[t] = Some(p);
+                }
+            }
+        }
+    }
+
+    // Rounded by hand, as `f64::round` needs std.
+    let mines_left = mines_left
+        .saturating_sub(deduced_mines)
+        .saturating_sub((estimated_mines + 0.5) as usize);
+    let frontier_size = groups.iter().map(|g| g.tiles.len()).sum::<usize>();
+    let weights = interior_weights(interior, mines_left, frontier_size);
+
+    // Layout counts for every group except one, built from both ends.
+    let mut prefix = vec![vec![1.0]];
+    for group in &groups {
+        prefix.push(convolve(prefix.last().unwrap(), &group.solutions));
+    }
+    let mut suffix = vec![vec![1.0]];
+    for group in groups.iter().rev() {
+        suffix.push(convolve(suffix.last().unwrap(), &group.solutions));
+    }
+    suffix.reverse();
+
+    for (i, group) in groups.iter().enumerate() {
+        let others = convolve(&prefix[i], &suffix[i + 1]);
+        let mut total = 0.0;
+        let mut tile_totals = vec![0.0; group.tiles.len()];
+
+        for (k, &count) in group.solutions.iter().enumerate() {
+            for (rest, &other) in others.iter().enumerate() {
+                let w = other * weights.get(k + rest).copied().unwrap_or(0.0);
+                total += count * w;
+                for (t, &m) in group.mines[k].iter().enumerate() {
+                    tile_totals[t] += m * w;
+                }
+            }
+        }
+
+        for (t, &tile) in group.tiles.iter().enumerate() {
+            // A zero total means the mine count rules out every layout, which
+            // only happens on boards that could not have come from a real game.
+            probabilities[tile] = Some(if total > 0.0 {
+                tile_totals[t] / total
+            } else {
+                0.5
+            });
+        }
+    }
+
+    if interior > 0 {
+        let all = &prefix[groups.len()];
+        let (mut total, mut expected) = (0.0, 0.0);
+        for (k, &count) in all.iter().enumerate() {
+            let w = count * weights.get(k).copied().unwrap_or(0.0);
+            total += w;
+            expected += w * mines_left.saturating_sub(k) as f64 / interior as f64;
+        }
+        let p = if total > 0.0 {
+            expected / total
+        } else {
+            mines_left as f64 / (interior + frontier_size) as f64
+        };
+
+        (0..width * height)
+            .filter(|&t| unknown[t] && !on_frontier[t])
+            .for_each(|t| probabilities[t] = Some(p.min(1.0)));
+    }
+
+    Analysis {
+        width,
+        height,
+        probabilities,
+    }
+}
+
+fn constraints(board: &Board, unknown: &[bool]) -> Vec<Constraint> {
+    let (width, height) = (board.width as i32, board.height as i32);
+    let mut constraints = vec![];
+
+    for y in 0..height {
+        for x in 0..width {
+            let count = match board.check(x as usize, y as usize) {
+                TileState::Count(n) => n as usize,
+                TileState::Empty => 0,
+                _ => continue,
+            };
+
+            let mut tiles = vec![];
+            let mut mines = 0;
+            for (nx, ny) in neighbours(x, y, width, height) {
+                let t = (ny * width + nx) as usize;
+                if unknown[t] {
+                    tiles.push(t);
+                } else if let TileState::Mine = board.check(nx as usize, ny as usize) {
+                    mines += 1;
+                }
+            }
+
+            if !tiles.is_empty() {
+                constraints.push(Constraint {
+                    tiles,
+                    mines: count.saturating_sub(mines),
+                });
+            }
+        }
+    }
+
+    constraints
+}
+
+fn neighbours(x: i32, y: i32, width: i32, height: i32) -> impl Iterator<Item = (i32, i32)> {
+    (-1..=1)
+        .flat_map(move |dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+        .filter(move |&(nx, ny)| {
+            (nx, ny) != (x, y) && nx >= 0 && ny >= 0 && nx < width && ny < height
+        })
+}
+
+/// Settles what single numbers, and pairs where one number's tiles all border
+/// the other, give away on their own. Settled tiles are taken out of
+/// `constraints`, which keeps the exhaustive search in `solve` small.
+fn deduce(constraints: &mut Vec<Constraint>, size: usize) -> Vec<(usize, bool)> {
+    let mut known: Vec<Option<bool>> = vec![None; size];
+    let mut found = vec![];
+
+    loop {
+        let mut settled = vec![];
+
+        for c in constraints.iter() {
+            if c.mines == 0 || c.mines == c.tiles.len() {
+                settled.extend(c.tiles.iter().map(|&t| (t, c.mines > 0)));
+            }
+        }
+
+        if settled.is_empty() {
+            let mut by_tile = vec![vec![]; size];
+            for (i, c) in constraints.iter().enumerate() {
+                for &t in &c.tiles {
+                    by_tile[t].push(i);
+                }
+            }
+
+            for (i, small) in constraints.iter().enumerate() {
+                let neighbours = small.tiles.iter().flat_map(|&t| &by_tile[t]);
+                for &j in neighbours {
+                    let big = &constraints[j];
+                    if i == j
+                        || big.tiles.len() <= small.tiles.len()
+                        || !small.tiles.iter().all(|t| big.tiles.contains(t))
+                    {
+                        continue;
+                    }
+
+                    let rest = big.tiles.iter().filter(|t| !small.tiles.contains(t));
+                    let extra = big.mines.saturating_sub(small.mines);
+                    if extra == 0 {
+                        settled.extend(rest.map(|&t| (t, false)));
+                    } else if extra == big.tiles.len() - small.tiles.len() {
+                        settled.extend(rest.map(|&t| (t, true)));
+                    }
+                }
+            }
+        }
+
+        if settled.is_empty() {
+            return found;
+        }
+
+        for (t, mine) in settled {
+            if known[t].is_none() {
+                known[t] = Some(mine);
+                found.push((t, mine));
+            }
+        }
+
+        for c in constraints.iter_mut() {
+            let mines = c.tiles.iter().filter(|&&t| known[t] == Some(true)).count();
+            c.mines = c.mines.saturating_sub(mines);
+            c.tiles.retain(|&t| known[t].is_none());
+        }
+        constraints.retain(|c| !c.tiles.is_empty());
+    }
+}
+
+/// Splits the constraints into groups that share no tiles. Tiles come out in
+/// the order they were reached, which keeps the search in `solve` local.
+fn split(constraints: &[Constraint], size: usize) -> Vec<(Vec<usize>, Vec<&Constraint>)> {
+    let mut by_tile = vec![vec![]; size];
+    for (i, c) in constraints.iter().enumerate() {
+        for &t in &c.tiles {
+            by_tile[t].push(i);
+        }
+    }
+
+    let mut seen = vec![false; constraints.len()];
+    let mut placed = vec![false; size];
+    let mut groups = vec![];
+
+    for start in 0..constraints.len() {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+
+        let mut queue = vec![start];
+        let mut tiles = vec![];
+        let mut i = 0;
+        while i < queue.len() {
+            for &t in &constraints[queue[i]].tiles {
+                if !placed[t] {
+                    placed[t] = true;
+                    tiles.push(t);
+                }
+                for &next in &by_tile[t] {
+                    if !seen[next] {
+                        seen[next] = true;
+                        queue.push(next);
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        groups.push((tiles, queue.iter().map(|&c| &constraints[c]).collect()));
+    }
+
+    groups
+}
+
+/// Counts every layout of `tiles` that satisfies `constraints`, or gives up
+/// past [`SEARCH_LIMIT`] steps.
+fn solve(tiles: &[usize], constraints: &[&Constraint]) -> Option<Group> {
+    let index = |t: usize| tiles.iter().position(|&x| x == t).unwrap();
+    let local = constraints
+        .iter()
+        .map(|c| {
+            (
+                c.tiles.iter().map(|&t| index(t)).collect::<Vec<_>>(),
+                c.mines,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut by_tile = vec![vec![]; tiles.len()];
+    for (i, (c_tiles, _)) in local.iter().enumerate() {
+        for &t in c_tiles {
+            by_tile[t].push(i);
+        }
+    }
+
+    let mut search = Search {
+        constraints: &local,
+        by_tile: &by_tile,
+        mines: vec![0; local.len()],
+        open: local.iter().map(|(t, _)| t.len()).collect(),
+        layout: vec![false; tiles.len()],
+        group: Group {
+            tiles: tiles.to_vec(),
+            solutions: vec![0.0; tiles.len() + 1],
+            mines: vec![vec![0.0; tiles.len()]; tiles.len() + 1],
+        },
+        steps: 0,
+    };
+
+    search.next(0, 0).then_some(search.group)
+}
+
+struct Search<'a> {
+    constraints: &'a [(Vec<usize>, usize)],
+    by_tile: &'a [Vec<usize>],
+    /// Mines placed so far around each constraint.
+    mines: Vec<usize>,
+    /// Tiles still undecided around each constraint.
+    open: Vec<usize>,
+    layout: Vec<bool>,
+    group: Group,
+    steps: u32,
+}
+
+impl Search<'_> {
+    /// Returns false once the step budget runs out.
+    fn next(&mut self, tile: usize, placed: usize) -> bool {
+        self.steps += 1;
+        if self.steps > SEARCH_LIMIT {
+            return false;
+        }
+
+        if tile == self.layout.len() {
+            self.group.solutions[placed] += 1.0;
+            for (t, &mine) in self.layout.iter().enumerate() {
+                if mine {
+                    self.group.mines[placed][t] += 1.0;
+                }
+            }
+            return true;
+        }
+
+        for mine in [false, true] {
+            self.layout[tile] = mine;
+            let mut fits = true;
+
+            for &c in &self.by_tile[tile] {
+                self.open[c] -= 1;
+                self.mines[c] += mine as usize;
+                let target = self.constraints[c].1;
+                fits &= self.mines[c] <= target && self.mines[c] + self.open[c] >= target;
+            }
+
+            let finished = !fits || self.next(tile + 1, placed + mine as usize);
+
+            for &c in &self.by_tile[tile] {
+                self.open[c] += 1;
+                self.mines[c] -= mine as usize;
+            }
+
+            if !finished {
+                return false;
+            }
+        }
+
+        self.layout[tile] = false;
+        true
+    }
+}
+
+/// A rough answer for groups too big to search: each tile takes the highest
+/// local density among the numbers around it.
+fn estimate(tiles: &[usize], constraints: &[&Constraint]) -> Vec<(usize, f64)> {
+    tiles
+        .iter()
+        .map(|&t| {
+            let p = constraints
+                .iter()
+                .filter(|c| c.tiles.contains(&t))
+                .map(|c| c.mines as f64 / c.tiles.len() as f64)
+                .fold(0.0, f64::max);
+            (t, p)
+        })
+        .collect()
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+
+    // Only the ratios matter, so keep the numbers from overflowing.
+    let max = out.iter().cloned().fold(0.0, f64::max);
+    if max > 0.0 {
+        out.iter_mut().for_each(|v| *v /= max);
+    }
+    out
+}
+
+/// `weights[k]` is proportional to the number of ways to place the mines left
+/// over when the frontier holds `k`, i.e. `C(interior, mines - k)`.
+///
+/// Starts from the largest term and walks outwards, so values only shrink and
+/// can underflow to zero but never overflow.
+fn interior_weights(interior: usize, mines: usize, frontier: usize) -> Vec<f64> {
+    let valid = |k: usize| k <= mines && mines - k <= interior;
+    let mut weights = vec![0.0; frontier + 1];
+
+    let Some(peak) = (0..=frontier)
+        .filter(|&k| valid(k))
+        .min_by_key(|&k| (2 * (mines - k)).abs_diff(interior))
+    else {
+        return weights;
+    };
+
+    weights[peak] = 1.0;
+    for k in peak + 1..=frontier {
+        if !valid(k) {
+            break;
+        }
+        // C(n, r - 1) / C(n, r) = r / (n - r + 1), with r = mines - k + 1
+        let r = (mines - k + 1) as f64;
+        weights[k] = weights[k - 1] * r / (interior as f64 - r + 1.0);
+    }
+    for k in (0..peak).rev() {
+        if !valid(k) {
+            break;
+        }
+        // C(n, r + 1) / C(n, r) = (n - r) / (r + 1), with r = mines - k - 1
+        let r = (mines - k - 1) as f64;
+        weights[k] = weights[k + 1] * (interior as f64 - r) / (r + 1.0);
+    }
+
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_force_a_mine_and_the_tiles_beside_it() {
+        let board = Board::from_rows(&["...", "...", "#*#"]);
+        let analysis = analyze(&board);
+
+        assert_eq!(analysis.probability(1, 2), Some(1.0));
+        assert_eq!(analysis.mine_tiles(), vec![(1, 2)]);
+        assert_eq!(analysis.safe_tiles(), vec![(0, 2), (2, 2)]);
+        assert_eq!(analysis.probability(1, 1), None);
+    }
+
+    #[test]
+    fn a_fifty_fifty_is_even() {
+        let board = Board::from_rows(&["#.", "*."]);
+        let analysis = analyze(&board);
+
+        assert_eq!(analysis.probability(0, 0), Some(0.5));
+        assert_eq!(analysis.probability(0, 1), Some(0.5));
+        assert!(analysis.safe_tiles().is_empty());
+        assert!(analysis.mine_tiles().is_empty());
+    }
+
+    #[test]
+    fn digging_a_sure_mine_is_a_mistake() {
+        let board = Board::from_rows(&["...", "...", "#*#"]);
+        let review = review(&board, 1, 2);
+
+        assert_eq!(review.verdict, Verdict::Mine);
+        assert_eq!(review.probability, 1.0);
+        assert_eq!(review.safe_tile, Some((0, 2)));
+        assert!(!review.forced());
+    }
+
+    #[test]
+    fn a_guess_with_nothing_safe_left_is_forced() {
+        let board = Board::from_rows(&["#.", "*."]);
+        let review = review(&board, 0, 1);
+
+        assert_eq!(review.verdict, Verdict::Guess);
+        assert_eq!(review.probability, 0.5);
+        assert_eq!(review.safe_tile, None);
+        assert!(review.forced());
+    }
+
+    #[test]
+    fn a_guess_with_a_safe_tile_left_is_not_forced() {
+        // The left column is a 50/50; the number under the mine at (3, 0)
+        // clears (4, 0).
+        let board = Board::from_rows(&["#..*#", "*...."]);
+        let review = review(&board, 0, 1);
+
+        assert_eq!(review.verdict, Verdict::Guess);
+        assert_eq!(review.probability, 0.5);
+        assert_eq!(review.safe_tile, Some((4, 0)));
+        assert!(!review.forced());
+    }
+}
@@ -0,0 +1,468 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use num_derive::FromPrimitive;
+use ratatui::style::{Color, Modifier};
+use serde::Deserialize;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::{color_depth::ColorDepth, config::config_dir};
+
+#[derive(Debug, Clone)]
+pub struct ThemeData {
+    pub name: String,
+    pub cursor: Color,
+    pub flag: Color,
+    pub question: Color,
+    pub safe_mark: Color,
+    /// Marks placed while trying out a hypothesis.
+    pub tentative: Color,
+    /// Behind the tiles around the number under the cursor.
+    pub neighbours: Color,
+    pub tile_fg: Color,
+    pub tile_bg: Option<Color>,
+    pub mine: Color,
+    pub numbers: [Color; 8],
+    pub number_modifiers: [Modifier; 8],
+    pub flag_modifier: Modifier,
+    pub border: Color,
+    pub menu_fg: Color,
+    pub menu_bg: Color,
+    pub menu_dim: Color,
+    pub menu_highlight: Color,
+    pub menu_accent: Color,
+}
+
+#[derive(EnumIter, Debug, PartialEq, Display, FromPrimitive, Default, Clone)]
+pub enum Theme {
+    #[default]
+    Default = 0,
+    Light,
+    Dark,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+const NUMBER_COLORS: [Color; 8] = [
+    Color::Indexed(39),
+    Color::Indexed(48),
+    Color::Indexed(175),
+    Color::Indexed(105),
+    Color::Indexed(162),
+    Color::Indexed(31),
+    Color::Indexed(255),
+    Color::Indexed(244),
+];
+
+/// Used when the modifiers option is on, so numbers and flags can be told apart
+/// without relying on color alone.
+const NUMBER_MODIFIERS: [Modifier; 8] = [
+    Modifier::empty(),
+    Modifier::BOLD,
+    Modifier::UNDERLINED,
+    Modifier::REVERSED,
+    Modifier::BOLD.union(Modifier::UNDERLINED),
+    Modifier::BOLD.union(Modifier::REVERSED),
+    Modifier::UNDERLINED.union(Modifier::REVERSED),
+    Modifier::BOLD
+        .union(Modifier::UNDERLINED)
+        .union(Modifier::REVERSED),
+];
+
+impl ThemeData {
+    pub fn new(theme: &Theme) -> Self {
+        let base = Self {
+            name: theme.to_string(),
+            cursor: Color::Indexed(190),
+            flag: Color::Rgb(227, 85, 85),
+            question: Color::Indexed(221),
+            safe_mark: Color::Indexed(114),
+            tentative: Color::Indexed(141),
+            neighbours: Color::Indexed(238),
+            tile_fg: Color::Gray,
+            tile_bg: None,
+            mine: Color::Red,
+            numbers: NUMBER_COLORS,
+            number_modifiers: NUMBER_MODIFIERS,
+            flag_modifier: Modifier::BOLD.union(Modifier::REVERSED),
+            border: Color::White,
+            menu_fg: Color::White,
+            menu_bg: Color::Reset,
+            menu_dim: Color::Rgb(48, 48, 48),
+            menu_highlight: Color::DarkGray,
+            menu_accent: Color::LightGreen,
+        };
+
+        match theme {
+            Theme::Default => base,
+            Theme::Light => Self {
+                tile_fg: Color::Rgb(233, 233, 233),
+                tile_bg: Some(Color::Rgb(205, 205, 205)),
+                neighbours: Color::Rgb(160, 160, 160),
+                ..base
+            },
+            Theme::Dark => Self {
+                cursor: Color::Rgb(56, 132, 133),
+                flag: Color::Rgb(227, 123, 87),
+                tile_fg: Color::Rgb(70, 70, 70),
+                tile_bg: Some(Color::Rgb(44, 44, 44)),
+                neighbours: Color::Rgb(28, 66, 67),
+                ..base
+            },
+            // The colorblind palettes are built from the Okabe-Ito set, picking
+            // the hues that stay distinct under each kind of color vision.
+            Theme::Deuteranopia => Self {
+                cursor: Color::Rgb(240, 228, 66),
+                flag: Color::Rgb(230, 159, 0),
+                question: Color::Rgb(204, 121, 167),
+                safe_mark: Color::Rgb(0, 114, 178),
+                tentative: Color::Rgb(86, 180, 233),
+                mine: Color::Rgb(213, 94, 0),
+                numbers: [
+                    Color::Rgb(86, 180, 233),
+                    Color::Rgb(230, 159, 0),
+                    Color::Rgb(204, 121, 167),
+                    Color::Rgb(0, 114, 178),
+                    Color::Rgb(213, 94, 0),
+                    Color::Rgb(240, 228, 66),
+                    Color::Rgb(255, 255, 255),
+                    Color::Rgb(153, 153, 153),
+                ],
+                ..base
+            },
+            Theme::Protanopia => Self {
+                cursor: Color::Rgb(240, 228, 66),
+                flag: Color::Rgb(86, 180, 233),
+                question: Color::Rgb(204, 121, 167),
+                safe_mark: Color::Rgb(0, 114, 178),
+                tentative: Color::Rgb(0, 158, 115),
+                mine: Color::Rgb(230, 159, 0),
+                numbers: [
+                    Color::Rgb(86, 180, 233),
+                    Color::Rgb(240, 228, 66),
+                    Color::Rgb(204, 121, 167),
+                    Color::Rgb(0, 114, 178),
+                    Color::Rgb(230, 159, 0),
+                    Color::Rgb(0, 158, 115),
+                    Color::Rgb(255, 255, 255),
+                    Color::Rgb(153, 153, 153),
+                ],
+                ..base
+            },
+            Theme::Tritanopia => Self {
+                cursor: Color::Rgb(0, 158, 115),
+                flag: Color::Rgb(204, 121, 167),
+                question: Color::Rgb(255, 255, 255),
+                safe_mark: Color::Rgb(86, 180, 233),
+                tentative: Color::Rgb(0, 114, 178),
+                mine: Color::Rgb(213, 94, 0),
+                numbers: [
+                    Color::Rgb(86, 180, 233),
+                    Color::Rgb(0, 158, 115),
+                    Color::Rgb(255, 140, 140),
+                    Color::Rgb(0, 114, 178),
+                    Color::Rgb(204, 121, 167),
+                    Color::Rgb(213, 94, 0),
+                    Color::Rgb(255, 255, 255),
+                    Color::Rgb(153, 153, 153),
+                ],
+                ..base
+            },
+            Theme::HighContrast => Self {
+                cursor: Color::Yellow,
+                flag: Color::LightMagenta,
+                question: Color::LightYellow,
+                safe_mark: Color::LightGreen,
+                tentative: Color::LightBlue,
+                neighbours: Color::Blue,
+                tile_fg: Color::White,
+                tile_bg: Some(Color::Black),
+                mine: Color::LightRed,
+                numbers: [
+                    Color::LightCyan,
+                    Color::LightGreen,
+                    Color::LightRed,
+                    Color::LightBlue,
+                    Color::LightYellow,
+                    Color::Cyan,
+                    Color::White,
+                    Color::Gray,
+                ],
+                menu_bg: Color::Black,
+                menu_highlight: Color::Blue,
+                menu_accent: Color::Yellow,
+                ..base
+            },
+        }
+    }
+
+    pub fn builtins() -> Vec<Self> {
+        Theme::iter().map(|t| Self::new(&t)).collect()
+    }
+
+    /// Maps every color to the nearest one `depth` can show.
+    pub fn with_depth(&self, depth: ColorDepth) -> Self {
+        let map = |c: Color| depth.map(c);
+        Self {
+            name: self.name.clone(),
+            cursor: map(self.cursor),
+            flag: map(self.flag),
+            question: map(self.question),
+            safe_mark: map(self.safe_mark),
+            tentative: map(self.tentative),
+            neighbours: map(self.neighbours),
+            tile_fg: map(self.tile_fg),
+            tile_bg: self.tile_bg.map(map),
+            mine: map(self.mine),
+            numbers: self.numbers.map(map),
+            number_modifiers: self.number_modifiers,
+            flag_modifier: self.flag_modifier,
+            border: map(self.border),
+            menu_fg: map(self.menu_fg),
+            menu_bg: map(self.menu_bg),
+            menu_dim: map(self.menu_dim),
+            menu_highlight: map(self.menu_highlight),
+            menu_accent: map(self.menu_accent),
+        }
+    }
+
+    pub fn number(&self, n: u8) -> Color {
+        self.numbers[(n.clamp(1, 8) - 1) as usize]
+    }
+
+    pub fn number_modifier(&self, n: u8) -> Modifier {
+        self.number_modifiers[(n.clamp(1, 8) - 1) as usize]
+    }
+
+    /// Reads a theme from a TOML file. Any color left out falls back to the
+    /// default theme, and the name falls back to the file stem.
+    pub fn from_file(path: &Path) -> Result<Self, ThemeError> {
+        let text = fs::read_to_string(path).map_err(|e| ThemeError::Io(path.into(), e))?;
+        let file: ThemeFile =
+            toml::from_str(&text).map_err(|e| ThemeError::Parse(path.into(), e))?;
+
+        let mut theme = Self {
+            name: match file.name {
+                Some(name) => name,
+                None => path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            },
+            ..Self::default()
+        };
+
+        let color = |field: &str, value: &Option<String>, current: Color| match value {
+            Some(v) => parse_color(path, field, v),
+            None => Ok(current),
+        };
+
+        theme.cursor = color("cursor", &file.cursor, theme.cursor)?;
+        theme.flag = color("flag", &file.flag, theme.flag)?;
+        theme.question = color("question", &file.question, theme.question)?;
+        theme.safe_mark = color("safe_mark", &file.safe_mark, theme.safe_mark)?;
+        theme.tentative = color("tentative", &file.tentative, theme.tentative)?;
+        theme.neighbours = color("neighbours", &file.neighbours, theme.neighbours)?;
+        theme.tile_fg = color("tile_fg", &file.tile_fg, theme.tile_fg)?;
+        theme.mine = color("mine", &file.mine, theme.mine)?;
+        theme.border = color("border", &file.border, theme.border)?;
+        theme.menu_fg = color("menu_fg", &file.menu_fg, theme.menu_fg)?;
+        theme.menu_bg = color("menu_bg", &file.menu_bg, theme.menu_bg)?;
+        theme.menu_dim = color("menu_dim", &file.menu_dim, theme.menu_dim)?;
+        theme.menu_highlight = color("menu_highlight", &file.menu_highlight, theme.menu_highlight)?;
+        theme.menu_accent = color("menu_accent", &file.menu_accent, theme.menu_accent)?;
+
+        theme.tile_bg = match file.tile_bg.as_deref() {
+            None | Some("none") => None,
+            Some(v) => Some(parse_color(path, "tile_bg", v)?),
+        };
+
+        if let Some(numbers) = file.numbers {
+            if numbers.len() != 8 {
+                return Err(ThemeError::NumberCount(
+                    path.into(),
+                    "numbers",
+                    numbers.len(),
+                ));
+            }
+            for (i, v) in numbers.iter().enumerate() {
+                theme.numbers[i] = parse_color(path, &format!("numbers[{}]", i), v)?;
+            }
+        }
+
+        if let Some(modifiers) = file.number_modifiers {
+            if modifiers.len() != 8 {
+                return Err(ThemeError::NumberCount(
+                    path.into(),
+                    "number_modifiers",
+                    modifiers.len(),
+                ));
+            }
+            for (i, v) in modifiers.iter().enumerate() {
+                theme.number_modifiers[i] =
+                    parse_modifier(path, &format!("number_modifiers[{}]", i), v)?;
+            }
+        }
+
+        if let Some(v) = file.flag_modifier {
+            theme.flag_modifier = parse_modifier(path, "flag_modifier", &v)?;
+        }
+
+        Ok(theme)
+    }
+}
+
+impl Default for ThemeData {
+    fn default() -> Self {
+        Self::new(&Theme::Default)
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    name: Option<String>,
+    cursor: Option<String>,
+    flag: Option<String>,
+    question: Option<String>,
+    safe_mark: Option<String>,
+    tentative: Option<String>,
+    neighbours: Option<String>,
+    tile_fg: Option<String>,
+    tile_bg: Option<String>,
+    mine: Option<String>,
+    numbers: Option<Vec<String>>,
+    number_modifiers: Option<Vec<String>>,
+    flag_modifier: Option<String>,
+    border: Option<String>,
+    menu_fg: Option<String>,
+    menu_bg: Option<String>,
+    menu_dim: Option<String>,
+    menu_highlight: Option<String>,
+    menu_accent: Option<String>,
+}
+
+fn parse_color(path: &Path, field: &str, value: &str) -> Result<Color, ThemeError> {
+    Color::from_str(value).map_err(|_| ThemeError::InvalidColor {
+        path: path.into(),
+        field: field.into(),
+        value: value.into(),
+    })
+}
+
+/// Accepts modifier names joined with `+`, e.g. `"bold+underline"`. An empty
+/// string or `"none"` clears all modifiers.
+fn parse_modifier(path: &Path, field: &str, value: &str) -> Result<Modifier, ThemeError> {
+    let mut modifier = Modifier::empty();
+
+    for name in value.split('+').map(|n| n.trim().to_lowercase()) {
+        modifier |= match name.as_str() {
+            "" | "none" => Modifier::empty(),
+            "bold" => Modifier::BOLD,
+            "dim" => Modifier::DIM,
+            "italic" => Modifier::ITALIC,
+            "underline" | "underlined" => Modifier::UNDERLINED,
+            "reverse" | "reversed" => Modifier::REVERSED,
+            "crossed_out" | "strikethrough" => Modifier::CROSSED_OUT,
+            _ => {
+                return Err(ThemeError::InvalidModifier {
+                    path: path.into(),
+                    field: field.into(),
+                    value: value.into(),
+                })
+            }
+        };
+    }
+
+    Ok(modifier)
+}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    InvalidColor {
+        path: PathBuf,
+        field: String,
+        value: String,
+    },
+    InvalidModifier {
+        path: PathBuf,
+        field: String,
+        value: String,
+    },
+    NumberCount(PathBuf, &'static str, usize),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(path, e) => write!(f, "could not read theme {}: {}", path.display(), e),
+            ThemeError::Parse(path, e) => write!(f, "malformed theme {}: {}", path.display(), e),
+            ThemeError::InvalidColor { path, field, value } => write!(
+                f,
+                "theme {}: invalid color {:?} for `{}` \
+                 (expected a name like \"red\", an index 0-255 or \"#rrggbb\")",
+                path.display(),
+                value,
+                field
+            ),
+            ThemeError::InvalidModifier { path, field, value } => write!(
+                f,
+                "theme {}: invalid modifier {:?} for `{}` \
+                 (expected names like \"bold\", \"underline\" or \"reverse\" joined with '+')",
+                path.display(),
+                value,
+                field
+            ),
+            ThemeError::NumberCount(path, field, n) => write!(
+                f,
+                "theme {}: `{}` needs exactly 8 entries, found {}",
+                path.display(),
+                field,
+                n
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+/// The `themes` folder inside the config directory.
+pub fn theme_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
+
+/// Loads every `*.toml` in the theme directory, sorted by file name, along with
+/// the errors of any that were skipped. A missing directory is not an error.
+pub fn load_themes() -> (Vec<ThemeData>, Vec<ThemeError>) {
+    let Some(dir) = theme_dir() else {
+        return (vec![], vec![]);
+    };
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return (vec![], vec![]),
+        Err(e) => return (vec![], vec![ThemeError::Io(dir, e)]),
+    };
+
+    let mut paths = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let (mut themes, mut errors) = (vec![], vec![]);
+    for path in paths {
+        match ThemeData::from_file(&path) {
+            Ok(theme) => themes.push(theme),
+            Err(e) => errors.push(e),
+        }
+    }
+    (themes, errors)
+}
@@ -1,18 +1,33 @@
+use std::time::Duration;
+
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Alignment::Center,
     prelude::{Buffer, Rect},
-    style::{Color, Style, Styled, Stylize},
-    text::{Line, Span},
+    style::{Style, Styled, Stylize},
+    text::Line,
     widgets::{Block, BorderType::Double, Padding, Paragraph, Widget},
 };
 
+use crate::{option_menu::TimerOption, theme::ThemeData, SafetyPolicy};
+
 #[derive(Debug, Clone, Default)]
 pub struct WinMenu {
-    pub time: u64,
+    pub time: Duration,
+    pub timer: TimerOption,
+    pub bbbv: u32,
+    pub clicks: u32,
+    pub chords: u32,
+    /// Won without placing a single flag.
+    pub no_flags: bool,
+    /// Lives left and lives started with, when playing with more than one.
+    pub lives: Option<(u32, u32)>,
+    /// How the first dig was protected.
+    pub safety: SafetyPolicy,
     pub restart: bool,
     pub options: bool,
     pub quit: bool,
+    pub theme: ThemeData,
 }
 
 impl WinMenu {
@@ -29,23 +44,39 @@ impl Widget for &WinMenu {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // Clear.render(area, buf);
         let win_block = Block::bordered()
-            .set_style(
-                Style::new()
-                    .fg(Color::from_u32(0x00303030))
-                    .bg(Color::Reset),
-            )
-            .border_style(Style::new().fg(Color::White).bg(Color::Reset))
+            .set_style(Style::new().fg(self.theme.menu_dim).bg(self.theme.menu_bg))
+            .border_style(Style::new().fg(self.theme.border).bg(self.theme.menu_bg))
             .border_type(Double)
             .padding(Padding::proportional(1))
             .title(" Congratulations! ")
             .title_alignment(Center);
         win_block.clone().render(area, buf);
-        Paragraph::new(vec![
+        let seconds = self.time.as_secs_f64().max(0.001);
+        let efficiency = self.bbbv as f64 / (self.clicks + self.chords).max(1) as f64 * 100.0;
+        let mut lines = vec![
             Line::raw(""),
-            Line::from(Span::from(format!("Time: {}s", self.time).fg(Color::White))),
-            Line::raw(""),
-        ])
-        .centered()
-        .render(win_block.inner(area), buf);
+            Line::from(
+                format!(
+                    "Time: {}s{}",
+                    self.timer.format(self.time, 3),
+                    if self.no_flags { " NF" } else { "" }
+                )
+                .fg(self.theme.menu_fg),
+            ),
+            Line::from(format!("3BV/s: {:.2}", self.bbbv as f64 / seconds).fg(self.theme.menu_fg)),
+            Line::from(format!("Clicks: {}", self.clicks).fg(self.theme.menu_fg)),
+            Line::from(format!("Chords: {}", self.chords).fg(self.theme.menu_fg)),
+            Line::from(format!("Efficiency: {:.0}%", efficiency).fg(self.theme.menu_fg)),
+            Line::from(format!("First dig: {}", self.safety).fg(self.theme.menu_fg)),
+        ];
+        if let Some((left, total)) = self.lives {
+            lines.push(Line::from(
+                format!("Lives: {}/{}", left, total).fg(self.theme.menu_fg),
+            ));
+        }
+        lines.push(Line::raw(""));
+        Paragraph::new(lines)
+            .centered()
+            .render(win_block.inner(area), buf);
     }
 }